@@ -1,5 +1,5 @@
 use dprint_core::configuration::{self, NewLineKind};
-use ebnf_fmt::configuration::{NewlineKind, QuoteStyle};
+use ebnf_fmt::configuration::{AlternativeSymbols, NewlineKind, QuoteStyle};
 use serde::Serialize;
 
 #[derive(Clone, Serialize)]
@@ -10,8 +10,11 @@ pub struct Configuration {
     pub new_line_kind: NewLineKind,
 
     pub quote_style: QuoteStyle,
+    pub alternative_symbols: AlternativeSymbols,
     pub ignore_rule_comment_text: String,
     pub multiline_comments_markdown: bool,
+    pub spaced_identifiers: bool,
+    pub unicode_identifiers: bool,
 }
 
 impl Configuration {
@@ -25,8 +28,11 @@ impl Configuration {
                 _ => NewlineKind::Unix,
             },
             quote_style: self.quote_style,
+            alternative_symbols: self.alternative_symbols,
             ignore_rule_comment_text: self.ignore_rule_comment_text.clone(),
             mutliline_comment_indent: self.indent_width as usize,
+            spaced_identifiers: self.spaced_identifiers,
+            unicode_identifiers: self.unicode_identifiers,
         }
     }
 }
@@ -42,8 +48,11 @@ impl Default for Configuration {
                 NewlineKind::Windows => NewLineKind::CarriageReturnLineFeed,
             },
             quote_style: ebnf_fmt_default.quote_style,
+            alternative_symbols: ebnf_fmt_default.alternative_symbols,
             ignore_rule_comment_text: "dprint-ignore".to_string(),
             multiline_comments_markdown: true,
+            spaced_identifiers: ebnf_fmt_default.spaced_identifiers,
+            unicode_identifiers: ebnf_fmt_default.unicode_identifiers,
         }
     }
 }
@@ -1,3 +1,9 @@
+// The plugin handler is only wired up by `generate_plugin_code!` on wasm targets
+#![cfg_attr(
+    not(all(target_arch = "wasm32", target_os = "unknown")),
+    allow(dead_code)
+)]
+
 mod configuration;
 mod plugin;
 mod resolve_config;
@@ -37,8 +37,11 @@ pub fn resolve_config(
         indent_width  global,
         new_line_kind global,
         quote_style,
+        alternative_symbols,
         ignore_rule_comment_text,
         multiline_comments_markdown,
+        spaced_identifiers,
+        unicode_identifiers,
     );
 
     diagnostics.extend(configuration::get_unknown_property_diagnostics(config));
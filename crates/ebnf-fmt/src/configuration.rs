@@ -7,8 +7,15 @@ pub struct Configuration {
     pub line_width: usize,
     pub newline_kind: NewlineKind,
     pub quote_style: QuoteStyle,
+    pub alternative_symbols: AlternativeSymbols,
     pub ignore_rule_comment_text: String,
     pub mutliline_comment_indent: usize,
+    /// Whether to allow meta identifiers containing spaces, see
+    /// [`LexerOptions::spaced_identifiers`](ebnf_parser::LexerOptions::spaced_identifiers)
+    pub spaced_identifiers: bool,
+    /// Whether to allow meta identifiers with Unicode letters, see
+    /// [`LexerOptions::unicode_identifiers`](ebnf_parser::LexerOptions::unicode_identifiers)
+    pub unicode_identifiers: bool,
 }
 
 impl Default for Configuration {
@@ -17,8 +24,11 @@ impl Default for Configuration {
             line_width: 100,
             newline_kind: NewlineKind::Unix,
             quote_style: QuoteStyle::Single,
+            alternative_symbols: AlternativeSymbols::Normalize,
             ignore_rule_comment_text: "ebnf-fmt ignore".to_string(),
             mutliline_comment_indent: 2,
+            spaced_identifiers: false,
+            unicode_identifiers: false,
         }
     }
 }
@@ -31,6 +41,18 @@ pub enum QuoteStyle {
     Double,
 }
 
+/// How to print the alternative representations of symbols defined in ISO 14977 §7.x, like `/`
+/// for `|` or `(: :)` for `{ }`
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "strum", derive(strum::EnumString))]
+pub enum AlternativeSymbols {
+    /// Print every symbol as it was written in the input
+    Keep,
+    /// Replace alternative representations with the standard symbols
+    Normalize,
+}
+
 #[derive(Clone, Copy, Debug)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[cfg_attr(feature = "strum", derive(strum::EnumString))]
@@ -1,10 +1,7 @@
-use std::vec;
+use ebnf_parser::{ast::*, CommentMap, ParseResult, Token, TokenKind};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
-#[cfg(debug_assertions)]
-use ebnf_parser::Token;
-use ebnf_parser::{ast::*, CommentMap, ParseResult, TokenKind};
-
-use crate::configuration::{Configuration, NewlineKind, QuoteStyle};
+use crate::configuration::{AlternativeSymbols, Configuration, NewlineKind, QuoteStyle};
 
 enum Special {
     /// A newline according to the current config
@@ -55,10 +52,7 @@ where
     indent: usize,
     output: String,
     curr_line_len: usize,
-    #[cfg(debug_assertions)]
-    tokens: vec::IntoIter<Token<'src>>,
-    #[cfg(debug_assertions)]
-    curr_tok: Option<Token<'src>>,
+    tokens: Vec<Token<'src>>,
     tok_index: usize,
     comments: CommentMap<'src>,
     /// Is true while ignoring formatting for a rule to prevent pushing to `output` while still
@@ -85,19 +79,40 @@ where
             indent: 0,
             output: String::new(),
             curr_line_len: 0,
-            #[cfg(debug_assertions)]
-            tokens: parse_result.tokens.into_iter(),
-            #[cfg(debug_assertions)]
-            curr_tok: None,
-            tok_index: usize::MAX,
+            tokens: parse_result.tokens,
+            tok_index: 0,
             comments: parse_result.comments,
             no_push: false,
             comment_formatter,
         }
     }
 
+    /// Creates a formatter for a syntax without a source text, for example one built or rewritten
+    /// in code. The layout follows the configuration alone: there are no comments to keep, no
+    /// blank lines between rules and alternative symbols are always replaced.
+    ///
+    /// The syntax is expected to be valid, see [`ebnf_parser::builder::validate`].
+    pub fn from_syntax(
+        syntax: Syntax<'src>,
+        config: &'config Configuration,
+        comment_formatter: CommentFormatter,
+    ) -> Self {
+        Self {
+            syntax: Some(syntax),
+            text: "",
+            config,
+            indent: 0,
+            output: String::new(),
+            curr_line_len: 0,
+            tokens: vec![],
+            tok_index: 0,
+            comments: CommentMap::new(),
+            no_push: false,
+            comment_formatter,
+        }
+    }
+
     pub fn format(mut self) -> String {
-        self.next_tok();
         let syntax = self
             .syntax
             .take()
@@ -106,14 +121,6 @@ where
         self.output
     }
 
-    fn next_tok(&mut self) {
-        #[cfg(debug_assertions)]
-        {
-            self.curr_tok = self.tokens.next();
-        }
-        self.tok_index = self.tok_index.wrapping_add(1);
-    }
-
     fn push(&mut self, kind: PushKind) {
         match kind {
             PushKind::Char(c) => self.push_char(c),
@@ -126,7 +133,7 @@ where
         if self.no_push {
             return;
         }
-        self.curr_line_len += 1;
+        self.curr_line_len += char.width().unwrap_or(0);
         self.output.push(char);
     }
 
@@ -134,7 +141,7 @@ where
         if self.no_push {
             return;
         }
-        self.curr_line_len += text.chars().count();
+        self.curr_line_len += text.width();
         self.output.push_str(text);
     }
 
@@ -177,17 +184,16 @@ where
 
     fn push_token(&mut self, token: TokenKind, prefix: Option<PushKind>, suffix: Option<PushKind>) {
         self.check_comments();
-        #[cfg(debug_assertions)]
-        {
-            debug_assert_eq!(
-                token,
-                self.curr_tok
-                    .as_ref()
-                    .unwrap_or_else(|| panic!("expected TokenKind {:?} but was None", token))
-                    .kind
-            );
-        }
-        self.next_tok();
+        let curr_tok = self.tokens.get(self.tok_index);
+        debug_assert!(
+            !self.has_source() || curr_tok.is_some_and(|tok| tok.kind == token),
+            "expected TokenKind {:?} but was {:?}",
+            token,
+            curr_tok
+        );
+        let text = self.text;
+        let source_text = curr_tok.map(|tok| tok.span.slice(text));
+        self.tok_index += 1;
 
         if let Some(prefix) = prefix {
             self.push(prefix);
@@ -201,9 +207,18 @@ where
                     QuoteStyle::Double => '"',
                 };
                 self.push_char(quote);
-                self.push_str(text);
+                self.push_str(&text);
                 self.push_char(quote);
             }
+            TokenKind::LBrace
+            | TokenKind::RBrace
+            | TokenKind::LBracket
+            | TokenKind::RBracket
+            | TokenKind::Pipe
+            | TokenKind::Semicolon => match (self.config.alternative_symbols, source_text) {
+                (AlternativeSymbols::Keep, Some(source_text)) => self.push_str(source_text),
+                _ => self.push_str(&token.to_string()),
+            },
             _ => self.push_str(&token.to_string()),
         }
         if let Some(suffix) = suffix {
@@ -211,6 +226,11 @@ where
         }
     }
 
+    /// Whether the syntax was parsed from `text`, such that spans and `tokens` refer to it
+    fn has_source(&self) -> bool {
+        !self.tokens.is_empty()
+    }
+
     fn check_comments(&mut self) {
         if let Some(comments) = self.comments.remove(&self.tok_index) {
             let mut prev_comment: Option<Comment> = None;
@@ -223,7 +243,7 @@ where
                     }
                 }
 
-                self.format_comment(comment.text);
+                self.format_comment(&comment.text);
                 prev_comment = Some(comment);
             }
         }
@@ -236,6 +256,7 @@ where
                 .last()
                 .expect("Vector initialized with one element and never remove any element")
                 .last()
+                .filter(|_| self.has_source())
             {
                 let text_between = &self.text[prev_node.span.end..node.span.start];
                 if text_between.contains("\n\n") || text_between.contains("\r\n\r\n") {
@@ -260,7 +281,7 @@ where
     fn format_rule_block(&mut self, block: Vec<SyntaxRule>) {
         self.indent = block
             .iter()
-            .map(|rule| rule.name.len())
+            .map(|rule| UnicodeWidthStr::width(&*rule.name))
             .max()
             .expect("Every block consists of at least one rule")
             + 1;
@@ -277,7 +298,7 @@ where
                 .any(|comment| comment.text.contains(&self.config.ignore_rule_comment_text))
             {
                 self.check_comments();
-                let raw_text = &self.text[node.span.start..node.span.end];
+                let raw_text = node.span.slice(self.text);
                 for line in raw_text.split('\n') {
                     self.push_str(line.trim_end_matches('\r'));
                     self.push_special(Special::Newline);
@@ -287,8 +308,9 @@ where
         }
 
         // Format
+        let name_len = UnicodeWidthStr::width(&*node.name);
         self.push_token(TokenKind::Identifier(node.name), None, None);
-        self.push_special(Special::RestIndent(node.name.len()));
+        self.push_special(Special::RestIndent(name_len));
         self.push_token(TokenKind::Equal, None, Some(' '.into()));
         self.format_definitions_list(node.definitions);
         self.push_token(
@@ -425,7 +447,7 @@ where
             self.push_special(Special::Newline);
 
             let current_comment_indent = text
-                .trim_start_matches(|c| c == '\n' || c == '\r')
+                .trim_start_matches(['\n', '\r'])
                 .chars()
                 .take_while(|c| *c == ' ')
                 .count();
@@ -2,17 +2,16 @@ pub mod configuration;
 mod formatter;
 
 pub use configuration::Configuration;
-use ebnf_parser::{error::SyntaxError, Lexer, Parser};
+use ebnf_parser::{
+    ast::Syntax,
+    builder::validate,
+    error::{GrammarError, SyntaxError},
+    Lexer, LexerOptions, ParseResult, Parser,
+};
 pub use formatter::Formatter;
 
 pub fn format_code(text: &str, config: &Configuration) -> Result<String, SyntaxError> {
-    Ok(Formatter::new(
-        Parser::new(Lexer::new(text)).parse()?,
-        text,
-        config,
-        |text| text,
-    )
-    .format())
+    Ok(Formatter::new(parse(text, config)?, text, config, |text| text).format())
 }
 
 pub fn format_code_with_comment_formatter(
@@ -20,18 +19,37 @@ pub fn format_code_with_comment_formatter(
     config: &Configuration,
     comment_formatter: impl FnMut(String) -> String,
 ) -> Result<String, SyntaxError> {
-    Ok(Formatter::new(
-        Parser::new(Lexer::new(text)).parse()?,
-        text,
-        config,
-        comment_formatter,
-    )
-    .format())
+    Ok(Formatter::new(parse(text, config)?, text, config, comment_formatter).format())
+}
+
+/// Prints a syntax which was not parsed from a source text, for example one built or rewritten in
+/// code, with the same layout as [`format_code`].
+///
+/// Returns an error if the syntax contains something that cannot be written in EBNF, like a
+/// meta identifier that is not a valid identifier or a terminal string containing both kinds of
+/// quotes.
+pub fn print_syntax(syntax: &Syntax, config: &Configuration) -> Result<String, GrammarError> {
+    validate(syntax, lexer_options(config))?;
+    Ok(Formatter::from_syntax(syntax.clone(), config, |text| text).format())
+}
+
+fn parse<'src>(text: &'src str, config: &Configuration) -> Result<ParseResult<'src>, SyntaxError> {
+    Parser::new(Lexer::with_options(text, lexer_options(config))).parse()
+}
+
+fn lexer_options(config: &Configuration) -> LexerOptions {
+    LexerOptions {
+        spaced_identifiers: config.spaced_identifiers,
+        unicode_identifiers: config.unicode_identifiers,
+        ..Default::default()
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::configuration::Configuration;
+    use ebnf_parser::ast::SyntacticPrimaryKind;
+
+    use crate::configuration::{AlternativeSymbols, Configuration};
 
     use super::*;
 
@@ -42,4 +60,85 @@ mod tests {
         println!("{output}");
         assert!(!output.ends_with("\n\n"));
     }
+
+    #[test]
+    fn print() {
+        let input = include_str!("../../ebnf-parser/grammar.ebnf");
+        let syntax = Parser::new(Lexer::new(input)).parse().unwrap().syntax;
+        let output = print_syntax(&syntax, &Configuration::default()).unwrap();
+        let reparsed = Parser::new(Lexer::new(&output)).parse().unwrap().syntax;
+        assert_eq!(reparsed.to_string(), syntax.to_string());
+
+        let syntax = Parser::new(Lexer::new(
+            "a = 2 * ( b | c ) - ( d ) , { 'e' }- ;\nlonger = \"'\" | ? f ? | ;",
+        ))
+        .parse()
+        .unwrap()
+        .syntax;
+        assert_eq!(
+            print_syntax(&syntax, &Configuration::default()).unwrap(),
+            "a      = 2 * ( b | c ) - ( d ) , { 'e' }- ;\nlonger = \"'\" | ? f ? | ;\n"
+        );
+
+        let mut syntax = Parser::new(Lexer::new("a = b ;")).parse().unwrap().syntax;
+        let primary = &mut syntax.rules[0].definitions[0].terms[0].factor.primary;
+        primary.kind = SyntacticPrimaryKind::MetaIdentifier("b | c".into());
+        assert_eq!(
+            print_syntax(&syntax, &Configuration::default()),
+            Err(GrammarError::InvalidIdentifier("b | c".to_string()))
+        );
+    }
+
+    #[test]
+    fn format_unterminated() {
+        assert!(format_code("a = 'abc ;\n", &Configuration::default()).is_err());
+        assert!(format_code("a = b ; (* abc\n", &Configuration::default()).is_err());
+    }
+
+    #[test]
+    fn format_alternative_symbols() {
+        let input = "a = (/ b /) ! (: c :) / d .\n";
+        let output = format_code(input, &Configuration::default()).unwrap();
+        assert_eq!(output, "a = [ b ] | { c } | d ;\n");
+
+        let config = Configuration {
+            alternative_symbols: AlternativeSymbols::Keep,
+            ..Default::default()
+        };
+        let output = format_code(input, &config).unwrap();
+        assert_eq!(output, input);
+    }
+
+    #[test]
+    fn format_spaced_identifiers() {
+        let config = Configuration {
+            spaced_identifiers: true,
+            ..Default::default()
+        };
+        let input = "syntactic  primary\t= meta\tidentifier | empty sequence ;\n";
+        let output = format_code(input, &config).unwrap();
+        assert_eq!(
+            output,
+            "syntactic primary = meta identifier | empty sequence ;\n"
+        );
+    }
+
+    #[test]
+    fn format_unicode_identifiers() {
+        let config = Configuration {
+            line_width: 25,
+            unicode_identifiers: true,
+            ..Default::default()
+        };
+        // Each CJK character is two columns wide
+        let input = "数字 = 'ゼロ' | 'いち' | 'に' | 'さん' ;\ngröße = 'äöü' | 'ß' ;\n";
+        let output = format_code(input, &config).unwrap();
+        assert_eq!(
+            output,
+            "数字  = 'ゼロ' | 'いち' | 'に'\n      | 'さん' ;\ngröße = 'äöü' | 'ß' ;\n"
+        );
+        let output = format_code("数字 = 'a' ;\nab = 'b' ;\n", &config).unwrap();
+        assert_eq!(output, "数字 = 'a' ;\nab   = 'b' ;\n");
+        assert!(format_code(input, &Configuration::default()).is_err());
+    }
 }
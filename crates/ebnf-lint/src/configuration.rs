@@ -0,0 +1,156 @@
+use std::{collections::HashMap, fmt::Display, str::FromStr};
+
+use ebnf_parser::LexerOptions;
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct Configuration {
+    /// Overrides the default level of individual lints
+    pub levels: HashMap<LintId, Level>,
+    /// Comments containing this text disable lints for the rule following them. When the text is
+    /// followed by a list of lint names, like `(* ebnf-lint ignore snake_case, unused_rule *)`,
+    /// only those lints are disabled.
+    pub ignore_rule_comment_text: String,
+    /// Whether to allow meta identifiers containing spaces, see
+    /// [`LexerOptions::spaced_identifiers`](ebnf_parser::LexerOptions::spaced_identifiers)
+    pub spaced_identifiers: bool,
+    /// Whether to allow meta identifiers with Unicode letters, see
+    /// [`LexerOptions::unicode_identifiers`](ebnf_parser::LexerOptions::unicode_identifiers)
+    pub unicode_identifiers: bool,
+}
+
+impl Default for Configuration {
+    fn default() -> Self {
+        Self {
+            levels: HashMap::new(),
+            ignore_rule_comment_text: "ebnf-lint ignore".to_string(),
+            spaced_identifiers: false,
+            unicode_identifiers: false,
+        }
+    }
+}
+
+impl Configuration {
+    /// The options to lex grammars with
+    pub fn lexer_options(&self) -> LexerOptions {
+        LexerOptions {
+            spaced_identifiers: self.spaced_identifiers,
+            unicode_identifiers: self.unicode_identifiers,
+            ..Default::default()
+        }
+    }
+
+    /// Returns the configured level of the lint, or its default level
+    pub fn level(&self, id: LintId) -> Level {
+        self.levels
+            .get(&id)
+            .copied()
+            .unwrap_or_else(|| id.default_level())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum LintId {
+    /// Rule names should be written in `snake_case`
+    SnakeCase,
+    /// Every rule except the first one should be referenced by another rule
+    UnusedRule,
+    /// Parentheses which do not change the meaning, like in `a = b , ( c , d ) ;`
+    RedundantGrouping,
+    /// An optional sequence directly inside another one, like `[ [ x ] ]`
+    NestedOptional,
+    /// An alternative which is written exactly like an earlier one, like `a | b | a`
+    DuplicateAlternative,
+    /// A sequence followed or preceded by a repetition of itself, like `x , { x }`, which can be
+    /// written as `{ x }-`
+    NonemptyRepetition,
+    /// Terminal strings should all use the same kind of quotes, unless they contain that quote
+    InconsistentQuotes,
+    /// A comment directive names a lint which does not exist
+    UnknownLint,
+}
+
+impl LintId {
+    pub const ALL: [LintId; 8] = [
+        LintId::SnakeCase,
+        LintId::UnusedRule,
+        LintId::RedundantGrouping,
+        LintId::NestedOptional,
+        LintId::DuplicateAlternative,
+        LintId::NonemptyRepetition,
+        LintId::InconsistentQuotes,
+        LintId::UnknownLint,
+    ];
+
+    /// The name used in configuration and comment directives
+    pub fn name(self) -> &'static str {
+        match self {
+            LintId::SnakeCase => "snake_case",
+            LintId::UnusedRule => "unused_rule",
+            LintId::RedundantGrouping => "redundant_grouping",
+            LintId::NestedOptional => "nested_optional",
+            LintId::DuplicateAlternative => "duplicate_alternative",
+            LintId::NonemptyRepetition => "nonempty_repetition",
+            LintId::InconsistentQuotes => "inconsistent_quotes",
+            LintId::UnknownLint => "unknown_lint",
+        }
+    }
+
+    pub fn default_level(self) -> Level {
+        Level::Warning
+    }
+}
+
+impl Display for LintId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for LintId {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        LintId::ALL
+            .into_iter()
+            .find(|id| id.name() == s)
+            .ok_or_else(|| format!("Unknown lint '{s}'"))
+    }
+}
+
+/// How a lint is reported
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum Level {
+    /// The lint is disabled
+    Allow,
+    Warning,
+    Error,
+}
+
+impl Display for Level {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Level::Allow => write!(f, "allow"),
+            Level::Warning => write!(f, "warning"),
+            Level::Error => write!(f, "error"),
+        }
+    }
+}
+
+impl FromStr for Level {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "allow" => Ok(Level::Allow),
+            "warning" => Ok(Level::Warning),
+            "error" => Ok(Level::Error),
+            _ => Err(format!("Unknown lint level '{s}'")),
+        }
+    }
+}
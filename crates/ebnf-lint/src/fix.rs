@@ -0,0 +1,105 @@
+use ebnf_parser::{
+    analysis::RuleIssue, ast::Syntax, error::SyntaxError, span::Span, Lexer, LexerOptions, Parser,
+};
+
+/// Replaces the text in `span` with `replacement`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextEdit {
+    pub span: Span,
+    pub replacement: String,
+}
+
+/// A set of edits which resolve a finding when applied together
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fix {
+    pub message: String,
+    pub edits: Vec<TextEdit>,
+}
+
+/// Suggests renaming a reference to an undefined rule to the most similar defined rule, if there
+/// is one that is similar enough to be a likely misspelling
+pub fn fix_rule_issue(issue: &RuleIssue, syntax: &Syntax) -> Option<Fix> {
+    let RuleIssue::Undefined { name, usage } = issue else {
+        return None;
+    };
+    let max_distance = (name.chars().count() / 3).max(1);
+    let (distance, closest) = syntax
+        .rules
+        .iter()
+        .map(|rule| (edit_distance(name, &rule.name), &rule.name))
+        .min_by_key(|(distance, _)| *distance)?;
+    (distance <= max_distance).then(|| Fix {
+        message: format!("rename to '{closest}'"),
+        edits: vec![TextEdit {
+            span: *usage,
+            replacement: closest.to_string(),
+        }],
+    })
+}
+
+/// Applies the fixes to the text and formats the result with `ebnf_fmt`.
+///
+/// Comments inside a replaced span are kept in front of the replacement. When the edits of a fix
+/// overlap with those of an earlier fix, the whole fix is skipped, so running the fixes again on
+/// the result may fix more.
+pub fn apply_fixes(
+    text: &str,
+    fixes: &[Fix],
+    config: &ebnf_fmt::Configuration,
+) -> Result<String, SyntaxError> {
+    let options = LexerOptions {
+        spaced_identifiers: config.spaced_identifiers,
+        unicode_identifiers: config.unicode_identifiers,
+        ..Default::default()
+    };
+    let parse_result = Parser::new(Lexer::with_options(text, options)).parse()?;
+    let mut comments: Vec<_> = parse_result.comments.into_values().flatten().collect();
+    comments.sort_by_key(|comment| comment.span.start);
+
+    let mut edits: Vec<&TextEdit> = vec![];
+    for fix in fixes {
+        let overlaps = fix.edits.iter().any(|edit| {
+            edits.iter().any(|other| {
+                edit.span.start < other.span.end && other.span.start < edit.span.end
+                    || edit.span == other.span
+            })
+        });
+        if !overlaps {
+            edits.extend(&fix.edits);
+        }
+    }
+    edits.sort_by_key(|edit| edit.span.start);
+
+    let mut output = String::new();
+    let mut index = 0;
+    for edit in edits {
+        output.push_str(&text[index..edit.span.start]);
+        for comment in comments.iter().filter(|comment| {
+            comment.span.start >= edit.span.start && comment.span.end <= edit.span.end
+        }) {
+            output.push_str(comment.span.slice(text));
+            output.push(' ');
+        }
+        output.push_str(&edit.replacement);
+        index = edit.span.end;
+    }
+    output.push_str(&text[index..]);
+
+    ebnf_fmt::format_code(&output, config)
+}
+
+/// The Levenshtein distance between two strings
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, a) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, b) in b.iter().enumerate() {
+            let substitution = diagonal + (a != *b) as usize;
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(row[j + 1] + 1);
+        }
+    }
+    row[b.len()]
+}
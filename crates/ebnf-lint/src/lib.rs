@@ -0,0 +1,188 @@
+pub mod configuration;
+mod fix;
+mod linter;
+
+pub use configuration::{Configuration, Level, LintId};
+use ebnf_parser::{analysis, error::SyntaxError, Lexer, ParseResult, Parser};
+pub use fix::{apply_fixes, fix_rule_issue, Fix, TextEdit};
+pub use linter::Lint;
+
+pub fn lint_code(text: &str, config: &Configuration) -> Result<Vec<Lint>, SyntaxError> {
+    let parse_result = Parser::new(Lexer::with_options(text, config.lexer_options())).parse()?;
+    Ok(lint(&parse_result, text, config))
+}
+
+/// Lints an already parsed syntax. The lints are sorted by their location.
+pub fn lint(parse_result: &ParseResult, text: &str, config: &Configuration) -> Vec<Lint> {
+    linter::Linter::new(parse_result, text, config).lint(&parse_result.syntax)
+}
+
+/// Applies the fixes of all lints and renames misspelled references to undefined rules, then
+/// formats the result using `fmt_config`. Identifiers are lexed as configured in `config`, the
+/// identifier options of `fmt_config` are ignored.
+pub fn fix_code(
+    text: &str,
+    config: &Configuration,
+    fmt_config: &ebnf_fmt::Configuration,
+) -> Result<String, SyntaxError> {
+    let parse_result = Parser::new(Lexer::with_options(text, config.lexer_options())).parse()?;
+    let fixes: Vec<Fix> = lint(&parse_result, text, config)
+        .into_iter()
+        .filter_map(|lint| lint.fix)
+        .chain(
+            analysis::check_rules(&parse_result.syntax, None)
+                .iter()
+                .filter_map(|issue| fix_rule_issue(issue, &parse_result.syntax)),
+        )
+        .collect();
+    let fmt_config = ebnf_fmt::Configuration {
+        spaced_identifiers: config.spaced_identifiers,
+        unicode_identifiers: config.unicode_identifiers,
+        ..fmt_config.clone()
+    };
+    apply_fixes(text, &fixes, &fmt_config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lints<'a>(text: &'a str, config: &Configuration) -> Vec<(LintId, &'a str)> {
+        lint_code(text, config)
+            .unwrap()
+            .into_iter()
+            .map(|lint| (lint.id, &text[lint.span.start..lint.span.end]))
+            .collect()
+    }
+
+    #[test]
+    fn lint_grammar() {
+        let text = include_str!("../../ebnf-parser/grammar.ebnf");
+        // `syntax` is not the first rule and `comment` is only referenced by itself
+        assert_eq!(
+            lints(text, &Configuration::default()),
+            [
+                (LintId::UnusedRule, "syntax"),
+                (LintId::UnusedRule, "comment")
+            ]
+        );
+    }
+
+    #[test]
+    fn lint_rules() {
+        let text = "syntax = rule , { rule } ;\nrule = ( name ) , '=' , [ [ \"x\" ] ] , ( 'a' | 'b' | 'a' ) ;\nname = { 'n' } , 'n' , ( 'a' , 'b' ) - 'c' ;\nRuleName = 'x' ;\n";
+        assert_eq!(
+            lints(text, &Configuration::default()),
+            [
+                (LintId::NonemptyRepetition, "rule , { rule }"),
+                (LintId::RedundantGrouping, "( name )"),
+                (LintId::NestedOptional, "[ \"x\" ]"),
+                (LintId::InconsistentQuotes, "\"x\""),
+                (LintId::DuplicateAlternative, "'a'"),
+                (LintId::NonemptyRepetition, "{ 'n' } , 'n'"),
+                (LintId::SnakeCase, "RuleName"),
+                (LintId::UnusedRule, "RuleName"),
+            ]
+        );
+    }
+
+    #[test]
+    fn lint_config() {
+        let text = "a = ( b ) ;\nb = 'b' ;\n(* ebnf-lint ignore unused_rule *)\nC = ( 'c' ) ;\n(* ebnf-lint ignore *)\nD = ( 'd' ) ;\n";
+        let config = Configuration {
+            levels: [(LintId::SnakeCase, Level::Error)].into(),
+            ..Default::default()
+        };
+        let lints = lint_code(text, &config).unwrap();
+        assert_eq!(
+            lints
+                .iter()
+                .map(|lint| (lint.id, lint.level))
+                .collect::<Vec<_>>(),
+            [
+                (LintId::RedundantGrouping, Level::Warning),
+                (LintId::SnakeCase, Level::Error),
+                (LintId::RedundantGrouping, Level::Warning),
+            ]
+        );
+
+        let config = Configuration {
+            levels: [(LintId::RedundantGrouping, Level::Allow)].into(),
+            ..Default::default()
+        };
+        assert_eq!(
+            lint_code(text, &config)
+                .unwrap()
+                .into_iter()
+                .map(|lint| lint.id)
+                .collect::<Vec<_>>(),
+            [LintId::SnakeCase]
+        );
+    }
+
+    #[test]
+    fn fix() {
+        let config = Configuration::default();
+        let fmt_config = ebnf_fmt::Configuration::default();
+        let text = "Syntax = rule , { rule } , \"x\" | ( rul ) ;\nrule = [ [ 'a' (* maybe *) ] ] | 'b' | 'a' , 'b' | 'b' ;\n";
+        assert_eq!(
+            fix_code(text, &config, &fmt_config).unwrap(),
+            "syntax = { rule }- , 'x'\n       | rule ;\nrule   = [ 'a' (* maybe *) ]\n       | 'b'\n       | 'a' , 'b' ;\n"
+        );
+    }
+
+    #[test]
+    fn lint_unknown_directive() {
+        let text = "a = B ;\n(* ebnf-lint ignore snakecase *)\nB = ( 'b' ) ;\n";
+        let lints = lint_code(text, &Configuration::default()).unwrap();
+        assert_eq!(
+            lints
+                .iter()
+                .map(|lint| (lint.id, &text[lint.span.start..lint.span.end]))
+                .collect::<Vec<_>>(),
+            [
+                (LintId::UnknownLint, "(* ebnf-lint ignore snakecase *)"),
+                (LintId::SnakeCase, "B"),
+                (LintId::RedundantGrouping, "( 'b' )"),
+            ]
+        );
+        assert_eq!(lints[0].message, "Unknown lint 'snakecase'");
+    }
+
+    #[test]
+    fn lint_empty_alternatives() {
+        for (text, duplicate, fixed) in [
+            ("a = 'x' | | ;", (10, 11), "a = 'x' | ;\n"),
+            ("a = | 'x' | ;", (10, 11), "a = | 'x' ;\n"),
+            ("a = 'x'||;", (8, 9), "a = 'x' | ;\n"),
+        ] {
+            let lints = lint_code(text, &Configuration::default()).unwrap();
+            assert_eq!(
+                lints
+                    .iter()
+                    .map(|lint| (lint.id, (lint.span.start, lint.span.end)))
+                    .collect::<Vec<_>>(),
+                [(LintId::DuplicateAlternative, duplicate)],
+                "{text}"
+            );
+            let fixed_text = fix_code(
+                text,
+                &Configuration::default(),
+                &ebnf_fmt::Configuration::default(),
+            )
+            .unwrap();
+            assert_eq!(fixed_text, fixed);
+        }
+    }
+
+    #[test]
+    fn fix_unicode_identifiers() {
+        let config = Configuration {
+            unicode_identifiers: true,
+            ..Default::default()
+        };
+        let text = "größe = ( 'x' ) ;\n";
+        let fixed = fix_code(text, &config, &ebnf_fmt::Configuration::default()).unwrap();
+        assert_eq!(fixed, "größe = 'x' ;\n");
+    }
+}
@@ -0,0 +1,492 @@
+use std::collections::HashSet;
+
+use ebnf_parser::{
+    ast::*,
+    diagnostic::{Diagnostic, Severity},
+    span::Span,
+    visit::{walk_syntactic_primary, walk_syntax_rule, Visitor},
+    CommentMap, ParseResult, Token, TokenKind,
+};
+
+use crate::{
+    configuration::{Configuration, Level, LintId},
+    fix::{Fix, TextEdit},
+};
+
+/// A style issue found by the linter
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Lint {
+    pub id: LintId,
+    /// The configured level, never [`Level::Allow`]
+    pub level: Level,
+    pub span: Span,
+    pub message: String,
+    /// Edits which resolve this lint, if the fix is obvious
+    pub fix: Option<Fix>,
+}
+
+impl Lint {
+    pub fn to_diagnostic(&self) -> Diagnostic {
+        let severity = match self.level {
+            Level::Error => Severity::Error,
+            Level::Allow | Level::Warning => Severity::Warning,
+        };
+        let mut diagnostic = Diagnostic::new(severity, self.span, &self.message)
+            .with_note(format!("`{}` is set to `{}`", self.id, self.level));
+        if let Some(fix) = &self.fix {
+            diagnostic = diagnostic.with_help(&fix.message);
+        }
+        diagnostic
+    }
+}
+
+/// Where a factor appears in its term
+#[derive(Clone, Copy)]
+enum Position {
+    /// The factor of a term. `sole` is true when the term is the only one in its definitions list.
+    Factor {
+        has_exception: bool,
+        sole: bool,
+    },
+    Exception,
+}
+
+pub(crate) struct Linter<'a, 'src> {
+    text: &'src str,
+    tokens: &'a [Token<'src>],
+    comments: &'a CommentMap<'src>,
+    config: &'a Configuration,
+    lints: Vec<Lint>,
+    /// Lints disabled for the current rule by a comment directive
+    suppressed: Vec<LintId>,
+    /// The quote used by the first terminal that could have used either quote
+    quote: Option<char>,
+}
+
+impl<'a, 'src> Linter<'a, 'src> {
+    pub(crate) fn new(
+        parse_result: &'a ParseResult<'src>,
+        text: &'src str,
+        config: &'a Configuration,
+    ) -> Self {
+        Self {
+            text,
+            tokens: &parse_result.tokens,
+            comments: &parse_result.comments,
+            config,
+            lints: vec![],
+            suppressed: vec![],
+            quote: None,
+        }
+    }
+
+    pub(crate) fn lint(mut self, syntax: &Syntax) -> Vec<Lint> {
+        let mut references = References::default();
+        references.visit_syntax(syntax);
+        let references = references.references;
+
+        for (index, rule) in syntax.rules.iter().enumerate() {
+            let (suppressed, unknown) = self.directive(rule.span.start);
+            self.suppressed = suppressed;
+            for (span, message) in unknown {
+                self.report(LintId::UnknownLint, span, message, None);
+            }
+            let name_span = self.tokens[self.token_index(rule.span.start)].span;
+
+            if !is_snake_case(&rule.name) {
+                let snake_name = to_snake_case(&rule.name);
+                let is_rule_name = |token: &&Token| match &token.kind {
+                    TokenKind::Identifier(name) => *name == rule.name,
+                    _ => false,
+                };
+                // Renaming is only obvious when it does not clash with another rule
+                let fix = (!snake_name.is_empty()
+                    && syntax.rules.iter().all(|rule| rule.name != snake_name))
+                .then(|| Fix {
+                    message: format!("rename it to '{snake_name}'"),
+                    edits: self
+                        .tokens
+                        .iter()
+                        .filter(is_rule_name)
+                        .map(|token| TextEdit {
+                            span: token.span,
+                            replacement: snake_name.clone(),
+                        })
+                        .collect(),
+                });
+                self.report(
+                    LintId::SnakeCase,
+                    name_span,
+                    format!("Rule name '{}' is not in snake_case", rule.name),
+                    fix,
+                );
+            }
+            if index != 0 && !references.contains(&*rule.name) {
+                self.report(
+                    LintId::UnusedRule,
+                    name_span,
+                    format!("Rule '{}' is never used", rule.name),
+                    None,
+                );
+            }
+            self.walk_definitions(&rule.definitions);
+        }
+
+        self.lints.sort_by_key(|lint| lint.span.start);
+        self.lints
+    }
+
+    fn report(&mut self, id: LintId, span: Span, message: String, fix: Option<Fix>) {
+        let level = self.config.level(id);
+        if level == Level::Allow || self.suppressed.contains(&id) {
+            return;
+        }
+        self.lints.push(Lint {
+            id,
+            level,
+            span,
+            message,
+            fix,
+        });
+    }
+
+    /// Returns the index of the first token starting at or after `offset`
+    fn token_index(&self, offset: usize) -> usize {
+        self.tokens
+            .partition_point(|token| token.span.start < offset)
+    }
+
+    fn tokens_in(&self, span: Span) -> &'a [Token<'src>] {
+        if span.is_empty() {
+            return &[];
+        }
+        &self.tokens[self.token_index(span.start)..self.token_index(span.end)]
+    }
+
+    /// Returns the span of a definition, or for an empty definition, whose span from the parser
+    /// ends before it starts, the span of the `|`, `=` or opening bracket before it
+    fn definition_span(&self, definition: &SingleDefinition) -> Span {
+        match definition.span.is_empty() {
+            true => self.tokens[self.token_index(definition.span.end) - 1].span,
+            false => definition.span,
+        }
+    }
+
+    fn same_tokens(&self, a: Span, b: Span) -> bool {
+        self.tokens_in(a)
+            .iter()
+            .map(|token| &token.kind)
+            .eq(self.tokens_in(b).iter().map(|token| &token.kind))
+    }
+
+    /// Returns the source text of the tokens in the span separated by spaces, leaving out comments
+    fn token_text(&self, span: Span) -> String {
+        self.tokens_in(span)
+            .iter()
+            .map(|token| token.span.slice(self.text))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Returns edits removing the first and last token in the span, like surrounding parentheses
+    fn remove_delimiters(&self, span: Span) -> Vec<TextEdit> {
+        let tokens = self.tokens_in(span);
+        [tokens.first(), tokens.last()]
+            .into_iter()
+            .flatten()
+            .map(|token| TextEdit {
+                span: token.span,
+                replacement: String::new(),
+            })
+            .collect()
+    }
+
+    /// Returns the lints disabled by the comments before the token at `offset`, and the spans and
+    /// messages of unknown lint names in them. A directive without lint names disables all lints.
+    fn directive(&self, offset: usize) -> (Vec<LintId>, Vec<(Span, String)>) {
+        let ignore_text = &self.config.ignore_rule_comment_text;
+        let Some(comments) = self.comments.get(&self.token_index(offset)) else {
+            return (vec![], vec![]);
+        };
+        let mut suppressed = vec![];
+        let mut unknown = vec![];
+        for comment in comments {
+            let Some(index) = comment.text.find(ignore_text.as_str()) else {
+                continue;
+            };
+            let names: Vec<&str> = comment.text[index + ignore_text.len()..]
+                .split(|c: char| c == ',' || c.is_whitespace())
+                .filter(|name| !name.is_empty())
+                .collect();
+            if names.is_empty() {
+                suppressed.extend(LintId::ALL);
+            }
+            for name in names {
+                match name.parse() {
+                    Ok(id) => suppressed.push(id),
+                    Err(message) => unknown.push((comment.span, message)),
+                }
+            }
+        }
+        (suppressed, unknown)
+    }
+
+    fn walk_definitions(&mut self, definitions: &[SingleDefinition]) {
+        self.check_duplicate_alternatives(definitions);
+        let sole = definitions.len() == 1 && definitions[0].terms.len() == 1;
+        for definition in definitions {
+            self.check_nonempty_repetition(definition);
+            for term in &definition.terms {
+                self.walk_factor(
+                    &term.factor,
+                    Position::Factor {
+                        has_exception: term.exception.is_some(),
+                        sole: sole && term.exception.is_none(),
+                    },
+                );
+                if let Some(exception) = &term.exception {
+                    self.walk_factor(exception, Position::Exception);
+                }
+            }
+        }
+    }
+
+    fn walk_factor(&mut self, factor: &SyntacticFactor, position: Position) {
+        let primary = &factor.primary;
+        match &primary.kind {
+            SyntacticPrimaryKind::GroupedSequence(inner) => {
+                if is_redundant_group(factor, inner, position) {
+                    self.report(
+                        LintId::RedundantGrouping,
+                        primary.span,
+                        "Unnecessary parentheses".to_string(),
+                        Some(Fix {
+                            message: "remove these parentheses".to_string(),
+                            edits: self.remove_delimiters(primary.span),
+                        }),
+                    );
+                }
+                self.walk_definitions(inner);
+            }
+            SyntacticPrimaryKind::OptionalSequence(inner) => {
+                if let Some(nested) = single_factor(inner) {
+                    if matches!(
+                        nested.primary.kind,
+                        SyntacticPrimaryKind::OptionalSequence(_)
+                    ) && nested.repetition.is_none()
+                    {
+                        self.report(
+                            LintId::NestedOptional,
+                            nested.span,
+                            "Optional sequence directly inside an optional sequence".to_string(),
+                            Some(Fix {
+                                message: "remove the inner brackets".to_string(),
+                                edits: self.remove_delimiters(nested.span),
+                            }),
+                        );
+                    }
+                }
+                self.walk_definitions(inner);
+            }
+            SyntacticPrimaryKind::RepeatedSequence(inner) => self.walk_definitions(inner),
+            SyntacticPrimaryKind::TerminalString(text) => self.check_quotes(text, primary.span),
+            SyntacticPrimaryKind::MetaIdentifier(_)
+            | SyntacticPrimaryKind::SpecialSequence(_)
+            | SyntacticPrimaryKind::EmptySequence => {}
+        }
+    }
+
+    /// Checks for alternatives which are written exactly like an earlier one
+    fn check_duplicate_alternatives(&mut self, definitions: &[SingleDefinition]) {
+        for (index, definition) in definitions.iter().enumerate().skip(1) {
+            if !definitions[..index]
+                .iter()
+                .any(|other| self.same_tokens(other.span, definition.span))
+            {
+                continue;
+            }
+            let span = self.definition_span(definition);
+            self.report(
+                LintId::DuplicateAlternative,
+                span,
+                "Duplicate alternative".to_string(),
+                Some(Fix {
+                    message: "remove this alternative".to_string(),
+                    // Also remove the separator before the alternative
+                    edits: vec![TextEdit {
+                        span: Span {
+                            start: self.definition_span(&definitions[index - 1]).end,
+                            end: span.end,
+                        },
+                        replacement: String::new(),
+                    }],
+                }),
+            );
+        }
+    }
+
+    /// Checks for `x , { x }` and `{ x } , x`
+    fn check_nonempty_repetition(&mut self, definition: &SingleDefinition) {
+        let terms = &definition.terms;
+        for (index, term) in terms.iter().enumerate() {
+            if term.exception.is_some() || term.factor.repetition.is_some() {
+                continue;
+            }
+            let SyntacticPrimaryKind::RepeatedSequence(inner) = &term.factor.primary.kind else {
+                continue;
+            };
+            let [repeated] = &inner[..] else {
+                continue;
+            };
+            if repeated.span.start == repeated.span.end {
+                continue;
+            }
+            let len = repeated.terms.len();
+            let before = index.checked_sub(len).map(|start| (start, index));
+            let after = Some((index + 1, index + 1 + len)).filter(|(_, end)| *end <= terms.len());
+            for (start, end) in [before, after].into_iter().flatten() {
+                let sequence = Span {
+                    start: terms[start].span.start,
+                    end: terms[end - 1].span.end,
+                };
+                if !self.same_tokens(sequence, repeated.span) {
+                    continue;
+                }
+                let span = Span {
+                    start: sequence.start.min(term.span.start),
+                    end: sequence.end.max(term.span.end),
+                };
+                let replacement = format!("{{ {} }}-", self.token_text(repeated.span));
+                self.report(
+                    LintId::NonemptyRepetition,
+                    span,
+                    "Sequence next to a repetition of itself".to_string(),
+                    Some(Fix {
+                        message: format!("write it as `{replacement}`"),
+                        edits: vec![TextEdit { span, replacement }],
+                    }),
+                );
+                break;
+            }
+        }
+    }
+
+    fn check_quotes(&mut self, text: &str, span: Span) {
+        if text.contains('\'') || text.contains('"') {
+            return;
+        }
+        let quote = self.text[span.start..].chars().next();
+        match self.quote {
+            None => self.quote = quote,
+            Some(expected) if Some(expected) != quote => self.report(
+                LintId::InconsistentQuotes,
+                span,
+                "Inconsistent quotes".to_string(),
+                Some(Fix {
+                    message: format!("use `{expected}` like the other terminal strings"),
+                    edits: vec![TextEdit {
+                        span,
+                        replacement: format!("{expected}{text}{expected}"),
+                    }],
+                }),
+            ),
+            Some(_) => {}
+        }
+    }
+}
+
+/// Returns whether the parentheses around `inner` can be removed without changing the meaning
+fn is_redundant_group(
+    factor: &SyntacticFactor,
+    inner: &[SingleDefinition],
+    position: Position,
+) -> bool {
+    let [definition] = inner else {
+        // Alternatives only need no grouping when they are all there is
+        return matches!(position, Position::Factor { sole: true, .. })
+            && factor.repetition.is_none();
+    };
+    let single = single_factor(inner)
+        .filter(|factor| factor.primary.kind != SyntacticPrimaryKind::EmptySequence);
+    match (position, factor.repetition) {
+        (_, Some(_)) => single.is_some_and(|factor| factor.repetition.is_none()),
+        // A sequence can be spliced into the surrounding sequence
+        (
+            Position::Factor {
+                has_exception: false,
+                ..
+            },
+            None,
+        ) => single.is_some() || definition.terms.len() > 1,
+        (Position::Factor { .. } | Position::Exception, None) => single.is_some(),
+    }
+}
+
+/// Returns the factor if the definitions list consists of exactly one factor
+fn single_factor<'a, 'src>(
+    definitions: &'a [SingleDefinition<'src>],
+) -> Option<&'a SyntacticFactor<'src>> {
+    match definitions {
+        [SingleDefinition { terms, .. }] => match &terms[..] {
+            [SyntacticTerm {
+                factor,
+                exception: None,
+                ..
+            }] => Some(factor),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Collects all rules referenced by other rules than the one being visited
+#[derive(Default)]
+struct References<'a> {
+    rule: &'a str,
+    references: HashSet<&'a str>,
+}
+
+impl<'a, 'src> Visitor<'a, 'src> for References<'a> {
+    fn visit_syntax_rule(&mut self, node: &'a SyntaxRule<'src>) {
+        self.rule = &node.name;
+        walk_syntax_rule(self, node);
+    }
+
+    fn visit_syntactic_primary(&mut self, node: &'a SyntacticPrimary<'src>) {
+        match &node.kind {
+            SyntacticPrimaryKind::MetaIdentifier(name) if name != self.rule => {
+                self.references.insert(name);
+            }
+            _ => walk_syntactic_primary(self, node),
+        }
+    }
+}
+
+fn is_snake_case(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_alphabetic() && !c.is_uppercase())
+        && name
+            .chars()
+            .all(|c| (c.is_alphanumeric() || c == '_') && !c.is_uppercase())
+        && !name.ends_with('_')
+        && !name.contains("__")
+}
+
+fn to_snake_case(name: &str) -> String {
+    let mut snake = String::new();
+    let mut prev_lower = false;
+    for c in name.chars() {
+        if c.is_alphanumeric() {
+            if c.is_uppercase() && prev_lower {
+                snake.push('_');
+            }
+            snake.extend(c.to_lowercase());
+            prev_lower = c.is_lowercase() || c.is_numeric();
+        } else {
+            if !snake.is_empty() && !snake.ends_with('_') {
+                snake.push('_');
+            }
+            prev_lower = false;
+        }
+    }
+    snake.trim_end_matches('_').to_string()
+}
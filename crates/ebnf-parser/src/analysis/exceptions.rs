@@ -0,0 +1,223 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{ast::*, diagnostic::Diagnostic, span::Span};
+
+use super::for_each_primary;
+
+/// A syntactic exception which does not follow ISO 14977 §4.7, which requires every exception to
+/// be replaceable by a factor containing no meta identifiers
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExceptionIssue<'a> {
+    /// The exception can reach a recursive rule, so it cannot be expanded into a factor without
+    /// meta identifiers
+    Recursive {
+        exception: Span,
+        /// The recursive rule and the references which lead from it back to itself
+        rule: &'a str,
+        cycle: Vec<(&'a str, Span)>,
+    },
+    /// The exception depends on a special sequence, whose meaning is unknown
+    SpecialSequence { exception: Span, special: Span },
+    /// The exception depends on an undefined rule, whose meaning is unknown
+    Undefined {
+        exception: Span,
+        name: &'a str,
+        usage: Span,
+    },
+}
+
+impl ExceptionIssue<'_> {
+    pub fn exception(&self) -> Span {
+        match self {
+            ExceptionIssue::Recursive { exception, .. }
+            | ExceptionIssue::SpecialSequence { exception, .. }
+            | ExceptionIssue::Undefined { exception, .. } => *exception,
+        }
+    }
+
+    pub fn to_diagnostic(&self) -> Diagnostic {
+        match self {
+            ExceptionIssue::Recursive {
+                exception,
+                rule,
+                cycle,
+            } => {
+                let chain = cycle
+                    .iter()
+                    .map(|(name, _)| *name)
+                    .chain([*rule])
+                    .collect::<Vec<_>>()
+                    .join(" -> ");
+                let mut diagnostic = Diagnostic::error(*exception, "Exception is not regular")
+                    .with_label(format!("depends on the recursive rule '{rule}'"))
+                    .with_note(format!("cycle: {chain}"))
+                    .with_note("ISO 14977 only allows exceptions without recursion");
+                for (name, reference) in cycle {
+                    diagnostic =
+                        diagnostic.with_related(*reference, format!("'{name}' recurses here"));
+                }
+                diagnostic
+            }
+            ExceptionIssue::SpecialSequence { exception, special } => {
+                Diagnostic::warning(*exception, "Meaning of exception cannot be decided")
+                    .with_related(*special, "depends on this special sequence")
+            }
+            ExceptionIssue::Undefined {
+                exception,
+                name,
+                usage,
+            } => Diagnostic::warning(*exception, "Meaning of exception cannot be decided")
+                .with_related(*usage, format!("depends on the undefined rule '{name}'")),
+        }
+    }
+}
+
+/// Checks every syntactic exception for recursion and for dependencies on special sequences or
+/// undefined rules. The issues are sorted by the location of their exception.
+pub fn check_exceptions<'a>(syntax: &'a Syntax) -> Vec<ExceptionIssue<'a>> {
+    let mut rules: HashMap<&str, &SyntaxRule> = HashMap::new();
+    for rule in &syntax.rules {
+        rules.entry(&rule.name).or_insert(rule);
+    }
+
+    let mut issues = vec![];
+    for rule in &syntax.rules {
+        for_each_exception(&rule.definitions, &mut |exception| {
+            check_exception(exception, &rules, &mut issues)
+        });
+    }
+    issues.sort_by_key(|issue| issue.exception().start);
+    issues
+}
+
+fn check_exception<'a>(
+    exception: &'a SyntacticException,
+    rules: &HashMap<&'a str, &'a SyntaxRule>,
+    issues: &mut Vec<ExceptionIssue<'a>>,
+) {
+    // Collect all primaries the exception depends on, following references into other rules
+    let mut visited = HashSet::new();
+    let mut queue = vec![];
+    for_each_factor_primary(exception, &mut |primary| queue.push(primary));
+    let mut special = None;
+    let mut undefined = None;
+    while let Some(primary) = queue.pop() {
+        match &primary.kind {
+            SyntacticPrimaryKind::SpecialSequence(_) => {
+                special.get_or_insert(primary.span);
+            }
+            SyntacticPrimaryKind::MetaIdentifier(name) => match rules.get(&**name) {
+                Some(rule) => {
+                    if visited.insert(&**name) {
+                        for_each_primary(&rule.definitions, &mut |primary| queue.push(primary));
+                    }
+                }
+                None => {
+                    undefined.get_or_insert((&**name, primary.span));
+                }
+            },
+            _ => {}
+        }
+    }
+
+    let mut reachable: Vec<_> = visited.into_iter().collect();
+    reachable.sort_by_key(|name| rules[name].span.start);
+    if let Some((rule, cycle)) = reachable
+        .into_iter()
+        .find_map(|name| Some((name, find_cycle(name, rules)?)))
+    {
+        issues.push(ExceptionIssue::Recursive {
+            exception: exception.span,
+            rule,
+            cycle,
+        });
+    }
+    if let Some(special) = special {
+        issues.push(ExceptionIssue::SpecialSequence {
+            exception: exception.span,
+            special,
+        });
+    }
+    if let Some((name, usage)) = undefined {
+        issues.push(ExceptionIssue::Undefined {
+            exception: exception.span,
+            name,
+            usage,
+        });
+    }
+}
+
+/// Returns the references which lead from the rule `start` back to itself, if there are any
+fn find_cycle<'a>(
+    start: &'a str,
+    rules: &HashMap<&'a str, &'a SyntaxRule>,
+) -> Option<Vec<(&'a str, Span)>> {
+    fn search<'a>(
+        name: &'a str,
+        start: &str,
+        rules: &HashMap<&'a str, &'a SyntaxRule>,
+        visited: &mut HashSet<&'a str>,
+        path: &mut Vec<(&'a str, Span)>,
+    ) -> bool {
+        let mut references = vec![];
+        for_each_primary(&rules[name].definitions, &mut |primary| {
+            if let SyntacticPrimaryKind::MetaIdentifier(next) = &primary.kind {
+                references.push((&**next, primary.span));
+            }
+        });
+        for (next, span) in references {
+            let Some((next, _)) = rules.get_key_value(next) else {
+                continue;
+            };
+            path.push((name, span));
+            if *next == start || (visited.insert(next) && search(next, start, rules, visited, path))
+            {
+                return true;
+            }
+            path.pop();
+        }
+        false
+    }
+
+    let mut path = vec![];
+    match search(start, start, rules, &mut HashSet::new(), &mut path) {
+        true => Some(path),
+        false => None,
+    }
+}
+
+/// Calls `f` for every exception in the definitions, including nested ones
+fn for_each_exception<'a, 'src>(
+    definitions: &'a [SingleDefinition<'src>],
+    f: &mut impl FnMut(&'a SyntacticException<'src>),
+) {
+    for definition in definitions {
+        for term in &definition.terms {
+            for factor in std::iter::once(&term.factor).chain(&term.exception) {
+                if let SyntacticPrimaryKind::OptionalSequence(definitions)
+                | SyntacticPrimaryKind::RepeatedSequence(definitions)
+                | SyntacticPrimaryKind::GroupedSequence(definitions) = &factor.primary.kind
+                {
+                    for_each_exception(definitions, f);
+                }
+            }
+            if let Some(exception) = &term.exception {
+                f(exception);
+            }
+        }
+    }
+}
+
+/// Calls `f` for the primary of the factor and every primary nested in it
+fn for_each_factor_primary<'a, 'src>(
+    factor: &'a SyntacticFactor<'src>,
+    f: &mut impl FnMut(&'a SyntacticPrimary<'src>),
+) {
+    f(&factor.primary);
+    if let SyntacticPrimaryKind::OptionalSequence(definitions)
+    | SyntacticPrimaryKind::RepeatedSequence(definitions)
+    | SyntacticPrimaryKind::GroupedSequence(definitions) = &factor.primary.kind
+    {
+        for_each_primary(definitions, f);
+    }
+}
@@ -0,0 +1,337 @@
+use std::{
+    collections::{BTreeSet, HashMap},
+    fmt::Display,
+};
+
+use crate::{ast::*, span::Span};
+
+/// A terminal symbol as seen by FIRST and FOLLOW sets. Special sequences are treated as opaque
+/// terminals that only match themselves.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Terminal<'a> {
+    String(&'a str),
+    Special(&'a str),
+    /// The end of the input, only contained in FOLLOW sets
+    End,
+}
+
+impl Display for Terminal<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Terminal::String(text) if text.contains('\'') => write!(f, "\"{text}\""),
+            Terminal::String(text) => write!(f, "'{text}'"),
+            Terminal::Special(text) => write!(f, "? {text} ?"),
+            Terminal::End => write!(f, "end of input"),
+        }
+    }
+}
+
+pub type TerminalSet<'a> = BTreeSet<Terminal<'a>>;
+
+/// Any part of the syntax tree for which nullability and FIRST sets can be computed
+pub trait Expression<'src> {
+    fn span(&self) -> Span;
+    /// Use [`FirstFollow::nullable`] instead
+    fn nullable_with(&self, sets: &FirstFollow<'_, 'src>) -> bool;
+    /// Use [`FirstFollow::first`] instead
+    fn first_with<'a>(&'a self, sets: &FirstFollow<'a, 'src>, first: &mut TerminalSet<'a>);
+}
+
+/// Nullability, FIRST and FOLLOW sets of every rule in a [`Syntax`]
+///
+/// References to undefined rules are treated as never matching anything, so they are not
+/// nullable and have an empty FIRST set. When a rule is defined multiple times, only the first
+/// definition is considered.
+#[derive(Debug, Clone)]
+pub struct FirstFollow<'a, 'src> {
+    rules: HashMap<&'a str, &'a SyntaxRule<'src>>,
+    nullable: HashMap<&'a str, bool>,
+    first: HashMap<&'a str, TerminalSet<'a>>,
+    follow: HashMap<&'a str, TerminalSet<'a>>,
+    /// FOLLOW sets of all sub-expressions, keyed by their span
+    expression_follow: HashMap<Span, TerminalSet<'a>>,
+}
+
+impl<'a, 'src> FirstFollow<'a, 'src> {
+    /// Computes all sets for the given syntax. When `start_rule` is `None`, the first rule is used
+    /// as the start rule, whose FOLLOW set contains [`Terminal::End`].
+    pub fn new(syntax: &'a Syntax<'src>, start_rule: Option<&str>) -> Self {
+        let mut rules = HashMap::new();
+        for rule in &syntax.rules {
+            rules.entry(&*rule.name).or_insert(rule);
+        }
+        let mut sets = Self {
+            nullable: rules.keys().map(|name| (*name, false)).collect(),
+            first: rules
+                .keys()
+                .map(|name| (*name, TerminalSet::new()))
+                .collect(),
+            follow: rules
+                .keys()
+                .map(|name| (*name, TerminalSet::new()))
+                .collect(),
+            rules,
+            expression_follow: HashMap::new(),
+        };
+
+        let rules: Vec<_> = sets
+            .rules
+            .iter()
+            .map(|(name, rule)| (*name, *rule))
+            .collect();
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for (name, rule) in &rules {
+                let nullable = rule.definitions.nullable_with(&sets);
+                let mut first = TerminalSet::new();
+                rule.definitions.first_with(&sets, &mut first);
+                if nullable != sets.nullable[name] || first.len() != sets.first[name].len() {
+                    changed = true;
+                }
+                *sets
+                    .nullable
+                    .get_mut(name)
+                    .expect("every rule has an entry") = nullable;
+                *sets.first.get_mut(name).expect("every rule has an entry") = first;
+            }
+        }
+
+        let start_rule = match start_rule {
+            Some(name) => sets.rules.get(name).map(|rule| &*rule.name),
+            None => syntax.rules.first().map(|rule| &*rule.name),
+        };
+        if let Some(start_rule) = start_rule {
+            sets.follow
+                .get_mut(start_rule)
+                .expect("every rule has an entry")
+                .insert(Terminal::End);
+        }
+
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for (name, rule) in &rules {
+                let follow = sets.follow[name].clone();
+                changed |= sets.walk_definitions(&rule.definitions, &follow);
+            }
+        }
+
+        sets
+    }
+
+    /// Returns whether the rule with the given name can derive the empty string
+    pub fn rule_nullable(&self, name: &str) -> Option<bool> {
+        self.nullable.get(name).copied()
+    }
+
+    pub fn rule_first(&self, name: &str) -> Option<&TerminalSet<'a>> {
+        self.first.get(name)
+    }
+
+    pub fn rule_follow(&self, name: &str) -> Option<&TerminalSet<'a>> {
+        self.follow.get(name)
+    }
+
+    /// Returns whether the expression can derive the empty string
+    pub fn nullable<E: Expression<'src> + ?Sized>(&self, expression: &'a E) -> bool {
+        expression.nullable_with(self)
+    }
+
+    /// Returns the set of terminals that can start a string derived from the expression
+    pub fn first<E: Expression<'src> + ?Sized>(&self, expression: &'a E) -> TerminalSet<'a> {
+        let mut first = TerminalSet::new();
+        expression.first_with(self, &mut first);
+        first
+    }
+
+    /// Returns the set of terminals that can follow the expression. Only available for
+    /// expressions that are part of the syntax these sets were computed for.
+    pub fn follow<E: Expression<'src> + ?Sized>(
+        &self,
+        expression: &'a E,
+    ) -> Option<&TerminalSet<'a>> {
+        self.expression_follow.get(&expression.span())
+    }
+
+    /// Adds `follow` to the FOLLOW set of the given span and returns whether it changed
+    fn add_follow(&mut self, span: Span, follow: &TerminalSet<'a>) -> bool {
+        let set = self.expression_follow.entry(span).or_default();
+        let len = set.len();
+        set.extend(follow);
+        set.len() != len
+    }
+
+    fn walk_definitions(
+        &mut self,
+        definitions: &'a [SingleDefinition<'src>],
+        follow: &TerminalSet<'a>,
+    ) -> bool {
+        let mut changed = self.add_follow(definitions.span(), follow);
+        for definition in definitions {
+            changed |= self.add_follow(definition.span, follow);
+            // Walk the terms backwards, so the FOLLOW set can be built up incrementally
+            let mut term_follow = follow.clone();
+            for term in definition.terms.iter().rev() {
+                changed |= self.add_follow(term.span, &term_follow);
+                changed |= self.walk_factor(&term.factor, &term_follow);
+                if let Some(exception) = &term.exception {
+                    changed |= self.walk_factor(exception, &term_follow);
+                }
+                if !self.nullable(term) {
+                    term_follow.clear();
+                }
+                term.first_with(self, &mut term_follow);
+            }
+        }
+        changed
+    }
+
+    fn walk_factor(&mut self, factor: &'a SyntacticFactor<'src>, follow: &TerminalSet<'a>) -> bool {
+        let mut changed = self.add_follow(factor.span, follow);
+        let mut primary_follow = follow.clone();
+        // `n * x` for n >= 2 means `x` can be followed by another `x`
+        if factor.repetition.is_some_and(|n| n >= 2) {
+            factor.primary.first_with(self, &mut primary_follow);
+        }
+        changed |= self.add_follow(factor.primary.span, &primary_follow);
+        match &factor.primary.kind {
+            SyntacticPrimaryKind::OptionalSequence(definitions)
+            | SyntacticPrimaryKind::GroupedSequence(definitions) => {
+                changed |= self.walk_definitions(definitions, &primary_follow)
+            }
+            SyntacticPrimaryKind::RepeatedSequence(definitions) => {
+                definitions.first_with(self, &mut primary_follow);
+                changed |= self.walk_definitions(definitions, &primary_follow);
+            }
+            SyntacticPrimaryKind::MetaIdentifier(name) => {
+                if let Some(set) = self.follow.get_mut(&**name) {
+                    let len = set.len();
+                    set.extend(&primary_follow);
+                    changed |= set.len() != len;
+                }
+            }
+            SyntacticPrimaryKind::TerminalString(_)
+            | SyntacticPrimaryKind::SpecialSequence(_)
+            | SyntacticPrimaryKind::EmptySequence => {}
+        }
+        changed
+    }
+}
+
+impl<'src> Expression<'src> for [SingleDefinition<'src>] {
+    fn span(&self) -> Span {
+        match (self.first(), self.last()) {
+            (Some(first), Some(last)) => Span::new(first.span.start, last.span.end),
+            _ => Span::new(0, 0),
+        }
+    }
+
+    fn nullable_with(&self, sets: &FirstFollow<'_, 'src>) -> bool {
+        self.iter().any(|definition| definition.nullable_with(sets))
+    }
+
+    fn first_with<'a>(&'a self, sets: &FirstFollow<'a, 'src>, first: &mut TerminalSet<'a>) {
+        for definition in self {
+            definition.first_with(sets, first);
+        }
+    }
+}
+
+impl<'src> Expression<'src> for SingleDefinition<'src> {
+    fn span(&self) -> Span {
+        self.span
+    }
+
+    fn nullable_with(&self, sets: &FirstFollow<'_, 'src>) -> bool {
+        self.terms.iter().all(|term| term.nullable_with(sets))
+    }
+
+    fn first_with<'a>(&'a self, sets: &FirstFollow<'a, 'src>, first: &mut TerminalSet<'a>) {
+        for term in &self.terms {
+            term.first_with(sets, first);
+            if !term.nullable_with(sets) {
+                break;
+            }
+        }
+    }
+}
+
+impl<'src> Expression<'src> for SyntacticTerm<'src> {
+    fn span(&self) -> Span {
+        self.span
+    }
+
+    /// The empty string is excluded when the exception is nullable
+    fn nullable_with(&self, sets: &FirstFollow<'_, 'src>) -> bool {
+        self.factor.nullable_with(sets)
+            && !self
+                .exception
+                .as_ref()
+                .is_some_and(|exception| exception.nullable_with(sets))
+    }
+
+    /// The exception can only remove strings, so this is an over-approximation
+    fn first_with<'a>(&'a self, sets: &FirstFollow<'a, 'src>, first: &mut TerminalSet<'a>) {
+        self.factor.first_with(sets, first)
+    }
+}
+
+impl<'src> Expression<'src> for SyntacticFactor<'src> {
+    fn span(&self) -> Span {
+        self.span
+    }
+
+    fn nullable_with(&self, sets: &FirstFollow<'_, 'src>) -> bool {
+        self.repetition == Some(0) || self.primary.nullable_with(sets)
+    }
+
+    fn first_with<'a>(&'a self, sets: &FirstFollow<'a, 'src>, first: &mut TerminalSet<'a>) {
+        if self.repetition != Some(0) {
+            self.primary.first_with(sets, first)
+        }
+    }
+}
+
+impl<'src> Expression<'src> for SyntacticPrimary<'src> {
+    fn span(&self) -> Span {
+        self.span
+    }
+
+    fn nullable_with(&self, sets: &FirstFollow<'_, 'src>) -> bool {
+        match &self.kind {
+            SyntacticPrimaryKind::OptionalSequence(_)
+            | SyntacticPrimaryKind::RepeatedSequence(_)
+            | SyntacticPrimaryKind::EmptySequence => true,
+            SyntacticPrimaryKind::GroupedSequence(definitions) => definitions.nullable_with(sets),
+            SyntacticPrimaryKind::MetaIdentifier(name) => {
+                sets.nullable.get(&**name).copied().unwrap_or(false)
+            }
+            SyntacticPrimaryKind::TerminalString(_) | SyntacticPrimaryKind::SpecialSequence(_) => {
+                false
+            }
+        }
+    }
+
+    fn first_with<'a>(&'a self, sets: &FirstFollow<'a, 'src>, first: &mut TerminalSet<'a>) {
+        match &self.kind {
+            SyntacticPrimaryKind::OptionalSequence(definitions)
+            | SyntacticPrimaryKind::RepeatedSequence(definitions)
+            | SyntacticPrimaryKind::GroupedSequence(definitions) => {
+                definitions.first_with(sets, first)
+            }
+            SyntacticPrimaryKind::MetaIdentifier(name) => {
+                if let Some(set) = sets.first.get(&**name) {
+                    first.extend(set);
+                }
+            }
+            SyntacticPrimaryKind::TerminalString(text) => {
+                first.insert(Terminal::String(text));
+            }
+            SyntacticPrimaryKind::SpecialSequence(text) => {
+                first.insert(Terminal::Special(text));
+            }
+            SyntacticPrimaryKind::EmptySequence => {}
+        }
+    }
+}
@@ -0,0 +1,152 @@
+use std::collections::HashMap;
+
+use crate::{ast::*, diagnostic::Diagnostic, span::Span};
+
+use super::FirstFollow;
+
+/// One rule of a left-recursive cycle
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LeftRecursionStep<'a> {
+    pub rule: &'a str,
+    pub rule_span: Span,
+    /// The meta identifier in this rule which refers to the next rule of the cycle
+    pub reference: Span,
+}
+
+/// A cycle of rules which can each start with a reference to the next one, so that a
+/// recursive-descent parser would recurse forever without consuming any input
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LeftRecursion<'a> {
+    /// The rules of the cycle in order, the last one refers back to the first one
+    pub cycle: Vec<LeftRecursionStep<'a>>,
+}
+
+impl LeftRecursion<'_> {
+    pub fn to_diagnostic(&self) -> Diagnostic {
+        let first = &self.cycle[0];
+        let chain = self
+            .cycle
+            .iter()
+            .map(|step| step.rule)
+            .chain([first.rule])
+            .collect::<Vec<_>>()
+            .join(" -> ");
+        let mut diagnostic = Diagnostic::error(
+            first.reference,
+            format!("Rule '{}' is left-recursive", first.rule),
+        )
+        .with_note(format!("cycle: {chain}"));
+        for (step, next) in self
+            .cycle
+            .iter()
+            .zip(self.cycle.iter().cycle().skip(1))
+            .skip(1)
+        {
+            diagnostic = diagnostic.with_related(
+                step.reference,
+                format!("'{}' can start with '{}' here", step.rule, next.rule),
+            );
+        }
+        diagnostic
+    }
+}
+
+/// Finds every left-recursive cycle of rules. Leading terms which can derive the empty string,
+/// like `[ x ]`, `{ x }` or nullable rules, are looked through. The `sets` are used to decide
+/// nullability.
+pub fn find_left_recursion<'a, 'src>(
+    syntax: &'a Syntax<'src>,
+    sets: &FirstFollow<'a, 'src>,
+) -> Vec<LeftRecursion<'a>> {
+    let mut indices = HashMap::new();
+    let mut rules = vec![];
+    for rule in &syntax.rules {
+        indices.entry(&*rule.name).or_insert_with(|| {
+            rules.push(rule);
+            rules.len() - 1
+        });
+    }
+
+    // For every rule, the rules it can start with and the first reference to each of them
+    let edges: Vec<Vec<(usize, Span)>> = rules
+        .iter()
+        .map(|rule| {
+            let mut edges = vec![];
+            left_references(&rule.definitions, sets, &mut |name, span| {
+                if let Some(&index) = indices.get(name) {
+                    if !edges.iter().any(|(other, _)| *other == index) {
+                        edges.push((index, span));
+                    }
+                }
+            });
+            edges
+        })
+        .collect();
+
+    // Every cycle is found once, starting at its rule with the lowest index
+    let mut cycles = vec![];
+    for start in 0..rules.len() {
+        let mut path = vec![];
+        find_cycles(start, start, &edges, &mut path, &mut cycles);
+    }
+
+    cycles
+        .into_iter()
+        .map(|cycle| LeftRecursion {
+            cycle: cycle
+                .into_iter()
+                .map(|(index, reference)| LeftRecursionStep {
+                    rule: &rules[index].name,
+                    rule_span: rules[index].span,
+                    reference,
+                })
+                .collect(),
+        })
+        .collect()
+}
+
+fn find_cycles(
+    start: usize,
+    node: usize,
+    edges: &[Vec<(usize, Span)>],
+    path: &mut Vec<(usize, Span)>,
+    cycles: &mut Vec<Vec<(usize, Span)>>,
+) {
+    for &(next, span) in &edges[node] {
+        path.push((node, span));
+        if next == start {
+            cycles.push(path.clone());
+        } else if next > start && !path.iter().any(|(index, _)| *index == next) {
+            find_cycles(start, next, edges, path, cycles);
+        }
+        path.pop();
+    }
+}
+
+/// Calls `f` for every meta identifier that can be the first thing matched by the definitions
+fn left_references<'a, 'src>(
+    definitions: &'a [SingleDefinition<'src>],
+    sets: &FirstFollow<'a, 'src>,
+    f: &mut impl FnMut(&'a str, Span),
+) {
+    for definition in definitions {
+        for term in &definition.terms {
+            if term.factor.repetition != Some(0) {
+                match &term.factor.primary.kind {
+                    SyntacticPrimaryKind::OptionalSequence(inner)
+                    | SyntacticPrimaryKind::RepeatedSequence(inner)
+                    | SyntacticPrimaryKind::GroupedSequence(inner) => {
+                        left_references(inner, sets, f)
+                    }
+                    SyntacticPrimaryKind::MetaIdentifier(name) => f(name, term.factor.primary.span),
+                    SyntacticPrimaryKind::TerminalString(_)
+                    | SyntacticPrimaryKind::SpecialSequence(_)
+                    | SyntacticPrimaryKind::EmptySequence => {}
+                }
+            }
+            if !sets.nullable(term) {
+                break;
+            }
+        }
+    }
+}
@@ -0,0 +1,183 @@
+use crate::{ast::*, diagnostic::Diagnostic, span::Span};
+
+use super::{FirstFollow, TerminalSet};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Ll1ConflictKind {
+    /// Two alternatives can start with the same terminal
+    FirstFirst { first: Span, second: Span },
+    /// One alternative can derive the empty string, and another alternative can start with a
+    /// terminal which may also follow the list of alternatives
+    FirstFollow { nullable: Span, alternative: Span },
+    /// The content of an optional or repeated sequence can start with a terminal which may also
+    /// follow the sequence, so it is unclear whether to enter it
+    Entry { sequence: Span },
+}
+
+/// A place where a parser cannot decide which way to go by looking at one terminal
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ll1Conflict<'a> {
+    pub kind: Ll1ConflictKind,
+    pub rule: &'a str,
+    pub rule_span: Span,
+    /// The terminals for which the parser cannot decide
+    pub terminals: TerminalSet<'a>,
+}
+
+impl Ll1Conflict<'_> {
+    /// The location this conflict is reported at
+    pub fn span(&self) -> Span {
+        match self.kind {
+            Ll1ConflictKind::FirstFirst { second, .. } => second,
+            Ll1ConflictKind::FirstFollow { alternative, .. } => alternative,
+            Ll1ConflictKind::Entry { sequence } => sequence,
+        }
+    }
+
+    pub fn to_diagnostic(&self) -> Diagnostic {
+        let terminals = self
+            .terminals
+            .iter()
+            .map(|terminal| terminal.to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+        match self.kind {
+            Ll1ConflictKind::FirstFirst { first, second } => Diagnostic::error(
+                second,
+                format!("FIRST/FIRST conflict in rule '{}'", self.rule),
+            )
+            .with_label(format!("this alternative can also start with {terminals}"))
+            .with_related(first, "conflicting alternative"),
+            Ll1ConflictKind::FirstFollow {
+                nullable,
+                alternative,
+            } => Diagnostic::error(
+                alternative,
+                format!("FIRST/FOLLOW conflict in rule '{}'", self.rule),
+            )
+            .with_label(format!("this alternative can start with {terminals}"))
+            .with_related(
+                nullable,
+                format!("this alternative can be empty and be followed by {terminals}"),
+            ),
+            Ll1ConflictKind::Entry { sequence } => Diagnostic::error(
+                sequence,
+                format!("FIRST/FOLLOW conflict in rule '{}'", self.rule),
+            )
+            .with_label(format!(
+                "both this sequence and what follows it can start with {terminals}"
+            )),
+        }
+    }
+}
+
+/// Reports every FIRST/FIRST and FIRST/FOLLOW conflict among alternatives and at the entry of
+/// optional and repeated sequences. The conflicts are sorted by their location.
+pub fn check_ll1<'a, 'src>(
+    syntax: &'a Syntax<'src>,
+    sets: &FirstFollow<'a, 'src>,
+) -> Vec<Ll1Conflict<'a>> {
+    let mut conflicts = vec![];
+    for rule in &syntax.rules {
+        check_definitions(&rule.definitions, rule, sets, &mut conflicts);
+    }
+    conflicts.sort_by_key(|conflict| conflict.span().start);
+    conflicts
+}
+
+fn check_definitions<'a, 'src>(
+    definitions: &'a [SingleDefinition<'src>],
+    rule: &'a SyntaxRule<'src>,
+    sets: &FirstFollow<'a, 'src>,
+    conflicts: &mut Vec<Ll1Conflict<'a>>,
+) {
+    let conflict = |kind, terminals| Ll1Conflict {
+        kind,
+        rule: &rule.name,
+        rule_span: rule.span,
+        terminals,
+    };
+
+    if definitions.len() > 1 {
+        let empty = TerminalSet::new();
+        let follow = sets.follow(definitions).unwrap_or(&empty);
+        let firsts: Vec<_> = definitions
+            .iter()
+            .map(|definition| sets.first(definition))
+            .collect();
+        for (index, second) in definitions.iter().enumerate() {
+            for (first, first_set) in definitions[..index].iter().zip(&firsts) {
+                let overlap: TerminalSet =
+                    first_set.intersection(&firsts[index]).copied().collect();
+                if !overlap.is_empty() {
+                    conflicts.push(conflict(
+                        Ll1ConflictKind::FirstFirst {
+                            first: first.span,
+                            second: second.span,
+                        },
+                        overlap,
+                    ));
+                }
+            }
+        }
+        for (nullable_index, nullable) in definitions.iter().enumerate() {
+            if !sets.nullable(nullable) {
+                continue;
+            }
+            for (index, (alternative, first_set)) in definitions.iter().zip(&firsts).enumerate() {
+                let overlap: TerminalSet = match sets.nullable(alternative) {
+                    // Report two empty alternatives only once. They conflict on everything that
+                    // can follow them.
+                    true if index > nullable_index => follow.clone(),
+                    true => continue,
+                    false => first_set.intersection(follow).copied().collect(),
+                };
+                if !overlap.is_empty() {
+                    conflicts.push(conflict(
+                        Ll1ConflictKind::FirstFollow {
+                            nullable: nullable.span,
+                            alternative: alternative.span,
+                        },
+                        overlap,
+                    ));
+                }
+            }
+        }
+    }
+
+    for definition in definitions {
+        for term in &definition.terms {
+            for factor in std::iter::once(&term.factor).chain(&term.exception) {
+                let primary = &factor.primary;
+                match &primary.kind {
+                    SyntacticPrimaryKind::OptionalSequence(inner)
+                    | SyntacticPrimaryKind::RepeatedSequence(inner) => {
+                        let follow = sets.follow(primary).cloned().unwrap_or_default();
+                        // An empty content is ambiguous on its own, not just with what follows
+                        let overlap: TerminalSet = match sets.nullable(&inner[..]) {
+                            true => sets.first(&inner[..]).union(&follow).copied().collect(),
+                            false => sets
+                                .first(&inner[..])
+                                .intersection(&follow)
+                                .copied()
+                                .collect(),
+                        };
+                        if !overlap.is_empty() {
+                            conflicts.push(conflict(
+                                Ll1ConflictKind::Entry {
+                                    sequence: primary.span,
+                                },
+                                overlap,
+                            ));
+                        }
+                        check_definitions(inner, rule, sets, conflicts);
+                    }
+                    SyntacticPrimaryKind::GroupedSequence(inner) => {
+                        check_definitions(inner, rule, sets, conflicts)
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,39 @@
+mod exceptions;
+mod first_follow;
+mod left_recursion;
+mod ll1;
+mod productivity;
+mod rules;
+
+pub use exceptions::*;
+pub use first_follow::*;
+pub use left_recursion::*;
+pub use ll1::*;
+pub use productivity::*;
+pub use rules::*;
+
+use crate::{
+    ast::*,
+    visit::{walk_syntactic_primary, Visitor},
+};
+
+/// Calls `f` for every primary in the given definitions, including those nested in sequences and
+/// those used as exceptions
+fn for_each_primary<'a, 'src>(
+    definitions: &'a [SingleDefinition<'src>],
+    f: &mut impl FnMut(&'a SyntacticPrimary<'src>),
+) {
+    struct Primaries<F>(F);
+
+    impl<'a, 'src: 'a, F: FnMut(&'a SyntacticPrimary<'src>)> Visitor<'a, 'src> for Primaries<F> {
+        fn visit_syntactic_primary(&mut self, node: &'a SyntacticPrimary<'src>) {
+            (self.0)(node);
+            walk_syntactic_primary(self, node);
+        }
+    }
+
+    let mut visitor = Primaries(f);
+    for definition in definitions {
+        visitor.visit_single_definition(definition);
+    }
+}
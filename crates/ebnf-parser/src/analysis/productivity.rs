@@ -0,0 +1,116 @@
+use std::collections::HashMap;
+
+use crate::{ast::*, diagnostic::Diagnostic, span::Span};
+
+/// A rule that cannot derive any finite string of terminals
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NonProductiveRule<'a> {
+    pub rule: &'a str,
+    pub rule_span: Span,
+    /// For every alternative, the first reference to a non-productive rule which prevents that
+    /// alternative from deriving a finite string
+    pub causes: Vec<(&'a str, Span)>,
+}
+
+impl NonProductiveRule<'_> {
+    pub fn to_diagnostic(&self) -> Diagnostic {
+        let mut diagnostic = Diagnostic::error(
+            self.rule_span,
+            format!("Rule '{}' never derives a finite string", self.rule),
+        )
+        .with_help("add an alternative that does not depend on this rule");
+        for (cause, reference) in &self.causes {
+            diagnostic = diagnostic.with_related(
+                *reference,
+                match *cause == self.rule {
+                    true => format!("'{cause}' always recurses here"),
+                    false => format!("'{cause}' never derives a finite string either"),
+                },
+            );
+        }
+        diagnostic
+    }
+}
+
+/// Finds all rules which cannot derive a finite string of terminals, like `a = 'x' , a ;`.
+///
+/// Special sequences are treated as opaque terminals. Exceptions only remove strings, so they are
+/// ignored. References to undefined rules are assumed to be productive, as they are already
+/// reported by [`check_rules`](super::check_rules). When a rule is defined multiple times, only
+/// the first definition is considered.
+pub fn find_non_productive<'a>(syntax: &'a Syntax) -> Vec<NonProductiveRule<'a>> {
+    let mut rules: HashMap<&str, &SyntaxRule> = HashMap::new();
+    for rule in &syntax.rules {
+        rules.entry(&rule.name).or_insert(rule);
+    }
+
+    let mut productive: HashMap<&str, bool> = rules.keys().map(|name| (*name, false)).collect();
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for (name, rule) in &rules {
+            if !productive[name] && definitions_productive(&rule.definitions, &productive) {
+                productive.insert(*name, true);
+                changed = true;
+            }
+        }
+    }
+
+    syntax
+        .rules
+        .iter()
+        .filter(|rule| !productive[&*rule.name] && std::ptr::eq(rules[&*rule.name], *rule))
+        .map(|rule| NonProductiveRule {
+            rule: &rule.name,
+            rule_span: rule.span,
+            causes: rule
+                .definitions
+                .iter()
+                .filter_map(|definition| blocking_reference(definition, &productive))
+                .collect(),
+        })
+        .collect()
+}
+
+fn definitions_productive(
+    definitions: &[SingleDefinition],
+    productive: &HashMap<&str, bool>,
+) -> bool {
+    definitions
+        .iter()
+        .any(|definition| blocking_reference(definition, productive).is_none())
+}
+
+/// Returns the first reference to a non-productive rule which prevents the definition from
+/// deriving a finite string, or `None` if the definition is productive
+fn blocking_reference<'a>(
+    definition: &'a SingleDefinition,
+    productive: &HashMap<&str, bool>,
+) -> Option<(&'a str, Span)> {
+    for term in &definition.terms {
+        if term.factor.repetition == Some(0) {
+            continue;
+        }
+        let primary = &term.factor.primary;
+        match &primary.kind {
+            SyntacticPrimaryKind::GroupedSequence(definitions) => {
+                if !definitions_productive(definitions, productive) {
+                    return definitions
+                        .iter()
+                        .find_map(|definition| blocking_reference(definition, productive));
+                }
+            }
+            SyntacticPrimaryKind::MetaIdentifier(name) => {
+                if productive.get(&**name) == Some(&false) {
+                    return Some((name, primary.span));
+                }
+            }
+            SyntacticPrimaryKind::OptionalSequence(_)
+            | SyntacticPrimaryKind::RepeatedSequence(_)
+            | SyntacticPrimaryKind::TerminalString(_)
+            | SyntacticPrimaryKind::SpecialSequence(_)
+            | SyntacticPrimaryKind::EmptySequence => {}
+        }
+    }
+    None
+}
@@ -0,0 +1,134 @@
+use std::{
+    borrow::Cow,
+    collections::{HashMap, HashSet},
+};
+
+use crate::{ast::*, diagnostic::Diagnostic, span::Span};
+
+use super::for_each_primary;
+
+/// A problem with the definitions of or references to syntax rules
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RuleIssue<'src> {
+    /// A meta identifier refers to a rule which is never defined
+    Undefined { name: Cow<'src, str>, usage: Span },
+    /// A rule is defined more than once
+    Duplicate {
+        name: Cow<'src, str>,
+        definition: Span,
+        first_definition: Span,
+    },
+    /// A rule cannot be reached from the start rule
+    Unreachable {
+        name: Cow<'src, str>,
+        definition: Span,
+        start_rule: Span,
+    },
+}
+
+impl RuleIssue<'_> {
+    /// The location this issue is reported at
+    pub fn span(&self) -> Span {
+        match self {
+            RuleIssue::Undefined { usage, .. } => *usage,
+            RuleIssue::Duplicate { definition, .. } => *definition,
+            RuleIssue::Unreachable { definition, .. } => *definition,
+        }
+    }
+
+    pub fn to_diagnostic(&self) -> Diagnostic {
+        match self {
+            RuleIssue::Undefined { name, usage } => {
+                Diagnostic::error(*usage, format!("Rule '{name}' is not defined"))
+                    .with_label("used here")
+            }
+            RuleIssue::Duplicate {
+                name,
+                definition,
+                first_definition,
+            } => Diagnostic::error(
+                *definition,
+                format!("Rule '{name}' is defined multiple times"),
+            )
+            .with_label("redefined here")
+            .with_related(*first_definition, "first defined here"),
+            RuleIssue::Unreachable {
+                name,
+                definition,
+                start_rule,
+            } => Diagnostic::warning(
+                *definition,
+                format!("Rule '{name}' is unreachable from the start rule"),
+            )
+            .with_related(*start_rule, "start rule defined here"),
+        }
+    }
+}
+
+/// Reports references to undefined rules, rules that are defined more than once and rules that
+/// cannot be reached from the start rule. When `start_rule` is `None`, the first rule of the
+/// syntax is used. The reachability check is skipped if no rule with the given name exists. The
+/// issues are sorted by their location.
+pub fn check_rules<'src>(syntax: &Syntax<'src>, start_rule: Option<&str>) -> Vec<RuleIssue<'src>> {
+    let mut issues = vec![];
+
+    let mut definitions: HashMap<&str, &SyntaxRule> = HashMap::new();
+    for rule in &syntax.rules {
+        match definitions.get(&*rule.name) {
+            Some(first) => issues.push(RuleIssue::Duplicate {
+                name: rule.name.clone(),
+                definition: rule.span,
+                first_definition: first.span,
+            }),
+            None => {
+                definitions.insert(&rule.name, rule);
+            }
+        }
+    }
+
+    for rule in &syntax.rules {
+        for_each_primary(&rule.definitions, &mut |primary| {
+            if let SyntacticPrimaryKind::MetaIdentifier(name) = &primary.kind {
+                if !definitions.contains_key(&**name) {
+                    issues.push(RuleIssue::Undefined {
+                        name: name.clone(),
+                        usage: primary.span,
+                    });
+                }
+            }
+        });
+    }
+
+    let start_rule = match start_rule {
+        Some(name) => definitions.get(name).copied(),
+        None => syntax.rules.first(),
+    };
+    if let Some(start_rule) = start_rule {
+        let mut reachable = HashSet::from([&*start_rule.name]);
+        let mut queue = vec![start_rule];
+        while let Some(rule) = queue.pop() {
+            for_each_primary(&rule.definitions, &mut |primary| {
+                if let SyntacticPrimaryKind::MetaIdentifier(name) = &primary.kind {
+                    if let Some(rule) = definitions.get(&**name) {
+                        if reachable.insert(&rule.name) {
+                            queue.push(rule);
+                        }
+                    }
+                }
+            });
+        }
+
+        for rule in &syntax.rules {
+            if !reachable.contains(&*rule.name) {
+                issues.push(RuleIssue::Unreachable {
+                    name: rule.name.clone(),
+                    definition: rule.span,
+                    start_rule: start_rule.span,
+                });
+            }
+        }
+    }
+
+    issues.sort_by_key(|issue| issue.span().start);
+    issues
+}
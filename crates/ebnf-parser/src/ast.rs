@@ -1,28 +1,69 @@
+//! The abstract syntax tree of an EBNF grammar.
+//!
+//! With the `serde` feature, all nodes can be serialized. The JSON shape is stable:
+//!
+//! - every node is an object with a `span` of the form `{ "start": 0, "end": 5 }`, holding byte
+//!   offsets into the source text, and the fields listed on its struct
+//! - optional fields, like the `exception` of a term or the `repetition` of a factor, are `null`
+//!   when missing
+//! - [`SyntacticPrimaryKind`] is an object with a single snake case key naming the variant, like
+//!   `{ "meta_identifier": "letter" }` or `{ "grouped_sequence": [ ... ] }`, except for
+//!   `"empty_sequence"`, which is a plain string
+//! - comments are objects with a `span` and the `text` between `(*` and `*)`
+//! - the `comments` of a [`ParseResult`](crate::ParseResult) are an object mapping the index of
+//!   the token following the comments to the list of comments, like
+//!   `{ "0": [ { "span": { "start": 0, "end": 7 }, "text": " a " } ] }`. The indices are
+//!   written as strings, since JSON keys are strings, and are sorted numerically.
+//!
+//! For example, `a = 'x' | b ;` is serialized to
+//!
+//! ```json
+//! { "span": { "start": 0, "end": 13 }, "rules": [
+//!   { "span": { "start": 0, "end": 13 }, "name": "a", "definitions": [
+//!     { "span": { "start": 4, "end": 7 }, "terms": [
+//!       { "span": { "start": 4, "end": 7 }, "factor":
+//!         { "span": { "start": 4, "end": 7 }, "repetition": null, "primary":
+//!           { "span": { "start": 4, "end": 7 }, "kind": { "terminal_string": "x" } } },
+//!         "exception": null } ] },
+//!     { "span": { "start": 10, "end": 11 }, "terms": [ ... ] } ] } ] }
+//! ```
+//!
+//! Deserialized nodes always own their text.
+
+use std::{
+    borrow::Cow,
+    fmt::{Display, Write},
+};
+
 use crate::{
     span::Span,
     token::{Token, TokenKind},
 };
 
 #[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Syntax<'src> {
     pub span: Span,
     pub rules: Vec<SyntaxRule<'src>>,
 }
 
 #[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SyntaxRule<'src> {
     pub span: Span,
-    pub name: &'src str,
+    pub name: Cow<'src, str>,
     pub definitions: Vec<SingleDefinition<'src>>,
 }
 
 #[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SingleDefinition<'src> {
     pub span: Span,
     pub terms: Vec<SyntacticTerm<'src>>,
 }
 
 #[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SyntacticTerm<'src> {
     pub span: Span,
     pub factor: SyntacticFactor<'src>,
@@ -32,6 +73,7 @@ pub struct SyntacticTerm<'src> {
 pub type SyntacticException<'src> = SyntacticFactor<'src>;
 
 #[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SyntacticFactor<'src> {
     pub span: Span,
     pub repetition: Option<usize>,
@@ -39,26 +81,30 @@ pub struct SyntacticFactor<'src> {
 }
 
 #[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SyntacticPrimary<'src> {
     pub span: Span,
     pub kind: SyntacticPrimaryKind<'src>,
 }
 
 #[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum SyntacticPrimaryKind<'src> {
     OptionalSequence(Vec<SingleDefinition<'src>>),
     RepeatedSequence(Vec<SingleDefinition<'src>>),
     GroupedSequence(Vec<SingleDefinition<'src>>),
-    MetaIdentifier(&'src str),
-    TerminalString(&'src str),
-    SpecialSequence(&'src str),
+    MetaIdentifier(Cow<'src, str>),
+    TerminalString(Cow<'src, str>),
+    SpecialSequence(Cow<'src, str>),
     EmptySequence,
 }
 
 #[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Comment<'src> {
     pub span: Span,
-    pub text: &'src str,
+    pub text: Cow<'src, str>,
 }
 
 impl<'src> TryFrom<Token<'src>> for Comment<'src> {
@@ -74,3 +120,244 @@ impl<'src> TryFrom<Token<'src>> for Comment<'src> {
         }
     }
 }
+
+fn owned(text: Cow<str>) -> Cow<'static, str> {
+    Cow::Owned(text.into_owned())
+}
+
+fn owned_definitions(definitions: Vec<SingleDefinition>) -> Vec<SingleDefinition<'static>> {
+    definitions
+        .into_iter()
+        .map(SingleDefinition::into_owned)
+        .collect()
+}
+
+impl Syntax<'_> {
+    /// Copies all borrowed text, so the syntax no longer depends on the source it was parsed from
+    pub fn into_owned(self) -> Syntax<'static> {
+        Syntax {
+            span: self.span,
+            rules: self.rules.into_iter().map(SyntaxRule::into_owned).collect(),
+        }
+    }
+}
+
+impl SyntaxRule<'_> {
+    pub fn into_owned(self) -> SyntaxRule<'static> {
+        SyntaxRule {
+            span: self.span,
+            name: owned(self.name),
+            definitions: owned_definitions(self.definitions),
+        }
+    }
+}
+
+impl SingleDefinition<'_> {
+    pub fn into_owned(self) -> SingleDefinition<'static> {
+        SingleDefinition {
+            span: self.span,
+            terms: self
+                .terms
+                .into_iter()
+                .map(SyntacticTerm::into_owned)
+                .collect(),
+        }
+    }
+}
+
+impl SyntacticTerm<'_> {
+    pub fn into_owned(self) -> SyntacticTerm<'static> {
+        SyntacticTerm {
+            span: self.span,
+            factor: self.factor.into_owned(),
+            exception: self.exception.map(SyntacticFactor::into_owned),
+        }
+    }
+}
+
+impl SyntacticFactor<'_> {
+    pub fn into_owned(self) -> SyntacticFactor<'static> {
+        SyntacticFactor {
+            span: self.span,
+            repetition: self.repetition,
+            primary: self.primary.into_owned(),
+        }
+    }
+}
+
+impl SyntacticPrimary<'_> {
+    pub fn into_owned(self) -> SyntacticPrimary<'static> {
+        SyntacticPrimary {
+            span: self.span,
+            kind: self.kind.into_owned(),
+        }
+    }
+}
+
+impl SyntacticPrimaryKind<'_> {
+    pub fn into_owned(self) -> SyntacticPrimaryKind<'static> {
+        match self {
+            SyntacticPrimaryKind::OptionalSequence(definitions) => {
+                SyntacticPrimaryKind::OptionalSequence(owned_definitions(definitions))
+            }
+            SyntacticPrimaryKind::RepeatedSequence(definitions) => {
+                SyntacticPrimaryKind::RepeatedSequence(owned_definitions(definitions))
+            }
+            SyntacticPrimaryKind::GroupedSequence(definitions) => {
+                SyntacticPrimaryKind::GroupedSequence(owned_definitions(definitions))
+            }
+            SyntacticPrimaryKind::MetaIdentifier(name) => {
+                SyntacticPrimaryKind::MetaIdentifier(owned(name))
+            }
+            SyntacticPrimaryKind::TerminalString(text) => {
+                SyntacticPrimaryKind::TerminalString(owned(text))
+            }
+            SyntacticPrimaryKind::SpecialSequence(text) => {
+                SyntacticPrimaryKind::SpecialSequence(owned(text))
+            }
+            SyntacticPrimaryKind::EmptySequence => SyntacticPrimaryKind::EmptySequence,
+        }
+    }
+}
+
+impl Comment<'_> {
+    pub fn into_owned(self) -> Comment<'static> {
+        Comment {
+            span: self.span,
+            text: owned(self.text),
+        }
+    }
+}
+
+/// Prints the syntax as EBNF on a single line per rule, with the tokens separated by single
+/// spaces. Grouping is explicit in the AST, so printing every
+/// [`SyntacticPrimaryKind::GroupedSequence`] in parentheses preserves the structure when parsing
+/// the output again. Use `ebnf_fmt::print_syntax` for formatted output.
+impl Display for Syntax<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for rule in &self.rules {
+            writeln!(f, "{rule}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Writes tokens separated by single spaces
+struct TokenWriter<'a, 'f> {
+    f: &'a mut std::fmt::Formatter<'f>,
+    first: bool,
+}
+
+impl<'a, 'f> TokenWriter<'a, 'f> {
+    fn new(f: &'a mut std::fmt::Formatter<'f>) -> Self {
+        Self { f, first: true }
+    }
+
+    fn token(&mut self, token: impl Display) -> std::fmt::Result {
+        if !self.first {
+            self.f.write_char(' ')?;
+        }
+        self.first = false;
+        write!(self.f, "{token}")
+    }
+}
+
+impl SyntaxRule<'_> {
+    fn write_tokens(&self, w: &mut TokenWriter) -> std::fmt::Result {
+        w.token(&self.name)?;
+        w.token('=')?;
+        write_definitions(w, &self.definitions)?;
+        w.token(';')
+    }
+}
+
+fn write_definitions(w: &mut TokenWriter, definitions: &[SingleDefinition]) -> std::fmt::Result {
+    for (index, definition) in definitions.iter().enumerate() {
+        if index != 0 {
+            w.token('|')?;
+        }
+        definition.write_tokens(w)?;
+    }
+    Ok(())
+}
+
+impl SingleDefinition<'_> {
+    fn write_tokens(&self, w: &mut TokenWriter) -> std::fmt::Result {
+        for (index, term) in self.terms.iter().enumerate() {
+            if index != 0 {
+                w.token(',')?;
+            }
+            term.write_tokens(w)?;
+        }
+        Ok(())
+    }
+}
+
+impl SyntacticTerm<'_> {
+    fn write_tokens(&self, w: &mut TokenWriter) -> std::fmt::Result {
+        self.factor.write_tokens(w)?;
+        if let Some(exception) = &self.exception {
+            w.token('-')?;
+            exception.write_tokens(w)?;
+        }
+        Ok(())
+    }
+}
+
+impl SyntacticFactor<'_> {
+    fn write_tokens(&self, w: &mut TokenWriter) -> std::fmt::Result {
+        if let Some(repetition) = self.repetition {
+            w.token(repetition)?;
+            w.token('*')?;
+        }
+        self.primary.kind.write_tokens(w)
+    }
+}
+
+impl SyntacticPrimaryKind<'_> {
+    fn write_tokens(&self, w: &mut TokenWriter) -> std::fmt::Result {
+        let (open, close, definitions) = match self {
+            SyntacticPrimaryKind::OptionalSequence(definitions) => ('[', ']', definitions),
+            SyntacticPrimaryKind::RepeatedSequence(definitions) => ('{', '}', definitions),
+            SyntacticPrimaryKind::GroupedSequence(definitions) => ('(', ')', definitions),
+            SyntacticPrimaryKind::MetaIdentifier(name) => return w.token(name),
+            SyntacticPrimaryKind::TerminalString(text) if text.contains('\'') => {
+                return w.token(format_args!("\"{text}\""))
+            }
+            SyntacticPrimaryKind::TerminalString(text) => return w.token(format_args!("'{text}'")),
+            SyntacticPrimaryKind::SpecialSequence(text) => {
+                return w.token(format_args!("? {text} ?"))
+            }
+            SyntacticPrimaryKind::EmptySequence => return Ok(()),
+        };
+        w.token(open)?;
+        write_definitions(w, definitions)?;
+        w.token(close)
+    }
+}
+
+macro_rules! display_tokens {
+    ($($node:ident),*) => {
+        $(
+            impl Display for $node<'_> {
+                fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                    self.write_tokens(&mut TokenWriter::new(f))
+                }
+            }
+        )*
+    };
+}
+
+display_tokens!(
+    SyntaxRule,
+    SingleDefinition,
+    SyntacticTerm,
+    SyntacticFactor,
+    SyntacticPrimaryKind
+);
+
+impl Display for SyntacticPrimary<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.kind)
+    }
+}
@@ -0,0 +1,395 @@
+//! Building grammars in code.
+//!
+//! A [`Grammar`] collects rules whose definitions are built from the functions of this module.
+//! [`Grammar::build`] validates the names and texts and returns a [`Syntax`] whose spans point
+//! into its [`Display`](std::fmt::Display) output, so printing it and parsing the result yields
+//! the same tree.
+//!
+//! ```
+//! use ebnf_parser::{builder::*, Lexer, Parser};
+//!
+//! let syntax = Grammar::new()
+//!     .rule("number", seq([id("digit"), rep(id("digit"))]))
+//!     .rule("digit", alt([t("0"), t("1")]))
+//!     .build()
+//!     .unwrap();
+//! let text = syntax.to_string();
+//! assert_eq!(text, "number = digit , { digit } ;\ndigit = '0' | '1' ;\n");
+//! assert_eq!(Parser::new(Lexer::new(&text)).parse().unwrap().syntax, syntax);
+//! ```
+
+use std::{borrow::Cow, collections::HashSet};
+
+use crate::{
+    ast::*,
+    error::GrammarError,
+    span::Span,
+    token::TokenKind,
+    visit::{walk_syntactic_primary, walk_syntax_rule, Visitor},
+    Lexer, LexerOptions,
+};
+
+/// The span of nodes before [`Grammar::build`] lays them out
+const UNSET: Span = Span::new(0, 0);
+
+/// A list of alternative definitions, the right hand side of a rule.
+///
+/// Expressions are combined without regard for precedence, for example a [`seq`] of [`alt`]s
+/// puts each alternative list in a group.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Expr(Vec<SingleDefinition<'static>>);
+
+/// Builds a [`Syntax`] rule by rule
+#[derive(Debug, Clone, Default)]
+pub struct Grammar {
+    rules: Vec<(String, Expr)>,
+    options: LexerOptions,
+}
+
+impl Grammar {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Validates identifiers like a [`Lexer`] with these options would lex them
+    pub fn with_options(options: LexerOptions) -> Self {
+        Self {
+            rules: vec![],
+            options,
+        }
+    }
+
+    pub fn rule(mut self, name: impl Into<String>, expr: Expr) -> Self {
+        self.rules.push((name.into(), expr));
+        self
+    }
+
+    /// Returns the syntax of all rules in the order they were added.
+    ///
+    /// Fails if there are no rules, a rule is defined twice, or a name or text cannot be written
+    /// in EBNF such that it is parsed back unchanged.
+    pub fn build(self) -> Result<Syntax<'static>, GrammarError> {
+        if self.rules.is_empty() {
+            return Err(GrammarError::Empty);
+        }
+        let mut names = HashSet::new();
+        let mut layout = Layout {
+            options: self.options,
+            offset: 0,
+            line_start: 0,
+        };
+        let mut rules = vec![];
+        for (name, Expr(definitions)) in self.rules {
+            if !names.insert(name.clone()) {
+                return Err(GrammarError::DuplicateRule(name));
+            }
+            check(
+                &SyntacticPrimaryKind::MetaIdentifier(Cow::Borrowed(&name)),
+                self.options,
+            )?;
+
+            let mut rule = SyntaxRule {
+                span: UNSET,
+                name: Cow::Owned(name),
+                definitions,
+            };
+            layout.rule(&mut rule)?;
+            layout.newline();
+            rules.push(rule);
+        }
+        Ok(Syntax {
+            span: Span::new(0, rules.last().map_or(0, |rule| rule.span.end)),
+            rules,
+        })
+    }
+}
+
+/// A terminal string
+pub fn t(text: impl Into<String>) -> Expr {
+    primary(SyntacticPrimaryKind::TerminalString(Cow::Owned(
+        text.into(),
+    )))
+}
+
+/// A reference to a rule
+pub fn id(name: impl Into<String>) -> Expr {
+    primary(SyntacticPrimaryKind::MetaIdentifier(Cow::Owned(
+        name.into(),
+    )))
+}
+
+/// A special sequence
+pub fn special(text: impl Into<String>) -> Expr {
+    primary(SyntacticPrimaryKind::SpecialSequence(Cow::Owned(
+        text.into(),
+    )))
+}
+
+/// The empty sequence. Like in parsed syntaxes, its span starts at the next token and ends at the
+/// previous one.
+pub fn empty() -> Expr {
+    primary(SyntacticPrimaryKind::EmptySequence)
+}
+
+/// The concatenation of the expressions, with alternatives grouped
+pub fn seq(exprs: impl IntoIterator<Item = Expr>) -> Expr {
+    let mut terms = vec![];
+    for Expr(mut definitions) in exprs {
+        match definitions.len() {
+            1 => terms.append(&mut definitions.remove(0).terms),
+            _ => terms.push(term(factor(SyntacticPrimaryKind::GroupedSequence(
+                definitions,
+            )))),
+        }
+    }
+    match terms.is_empty() {
+        true => empty(),
+        false => Expr(vec![SingleDefinition { span: UNSET, terms }]),
+    }
+}
+
+/// The alternatives of all expressions
+pub fn alt(exprs: impl IntoIterator<Item = Expr>) -> Expr {
+    let definitions: Vec<_> = exprs.into_iter().flat_map(|Expr(e)| e).collect();
+    match definitions.is_empty() {
+        true => empty(),
+        false => Expr(definitions),
+    }
+}
+
+/// An optional sequence, `[ expr ]`
+pub fn opt(expr: Expr) -> Expr {
+    primary(SyntacticPrimaryKind::OptionalSequence(expr.0))
+}
+
+/// A repeated sequence, `{ expr }`
+pub fn rep(expr: Expr) -> Expr {
+    primary(SyntacticPrimaryKind::RepeatedSequence(expr.0))
+}
+
+/// A grouped sequence, `( expr )`
+pub fn group(expr: Expr) -> Expr {
+    primary(SyntacticPrimaryKind::GroupedSequence(expr.0))
+}
+
+/// Exactly `count` repetitions, `count * expr`
+pub fn times(count: usize, expr: Expr) -> Expr {
+    let mut inner = expr.into_factor();
+    if inner.repetition.is_some() {
+        inner = factor(SyntacticPrimaryKind::GroupedSequence(vec![definition(
+            term(inner),
+        )]));
+    }
+    inner.repetition = Some(count);
+    Expr(vec![definition(term(inner))])
+}
+
+/// `expr` except `exception`, `expr - exception`
+pub fn except(expr: Expr, exception: Expr) -> Expr {
+    let mut term = term(expr.into_factor());
+    term.exception = Some(exception.into_factor());
+    Expr(vec![definition(term)])
+}
+
+impl Expr {
+    /// Returns the single factor of this expression, or a group containing it
+    fn into_factor(self) -> SyntacticFactor<'static> {
+        match <[_; 1]>::try_from(self.0) {
+            Ok([SingleDefinition { terms, .. }])
+                if terms.len() == 1 && terms[0].exception.is_none() =>
+            {
+                terms.into_iter().next().unwrap().factor
+            }
+            Ok(definitions) => factor(SyntacticPrimaryKind::GroupedSequence(definitions.into())),
+            Err(definitions) => factor(SyntacticPrimaryKind::GroupedSequence(definitions)),
+        }
+    }
+}
+
+fn primary(kind: SyntacticPrimaryKind<'static>) -> Expr {
+    Expr(vec![definition(term(factor(kind)))])
+}
+
+fn factor(kind: SyntacticPrimaryKind<'static>) -> SyntacticFactor<'static> {
+    SyntacticFactor {
+        span: UNSET,
+        repetition: None,
+        primary: SyntacticPrimary { span: UNSET, kind },
+    }
+}
+
+fn term(factor: SyntacticFactor<'static>) -> SyntacticTerm<'static> {
+    SyntacticTerm {
+        span: UNSET,
+        factor,
+        exception: None,
+    }
+}
+
+fn definition(term: SyntacticTerm<'static>) -> SingleDefinition<'static> {
+    SingleDefinition {
+        span: UNSET,
+        terms: vec![term],
+    }
+}
+
+/// Checks that every name and text in `syntax` can be written in EBNF, such that the lexer with
+/// these options reads them back unchanged. Fails for a syntax without rules, which cannot be
+/// parsed.
+pub fn validate(syntax: &Syntax, options: LexerOptions) -> Result<(), GrammarError> {
+    if syntax.rules.is_empty() {
+        return Err(GrammarError::Empty);
+    }
+    let mut validator = Validator {
+        options,
+        result: Ok(()),
+    };
+    validator.visit_syntax(syntax);
+    validator.result
+}
+
+struct Validator {
+    options: LexerOptions,
+    result: Result<(), GrammarError>,
+}
+
+impl<'ast, 'src> Visitor<'ast, 'src> for Validator {
+    fn visit_syntax_rule(&mut self, node: &'ast SyntaxRule<'src>) {
+        if self.result.is_ok() {
+            self.result = check(
+                &SyntacticPrimaryKind::MetaIdentifier(Cow::Borrowed(&node.name)),
+                self.options,
+            );
+            walk_syntax_rule(self, node);
+        }
+    }
+
+    fn visit_syntactic_primary(&mut self, node: &'ast SyntacticPrimary<'src>) {
+        if self.result.is_ok() {
+            self.result = check(&node.kind, self.options);
+            walk_syntactic_primary(self, node);
+        }
+    }
+}
+
+/// Checks that a leaf is lexed back to the same token
+fn check(kind: &SyntacticPrimaryKind, options: LexerOptions) -> Result<(), GrammarError> {
+    let (expected, error) = match kind {
+        SyntacticPrimaryKind::MetaIdentifier(name) => (
+            TokenKind::Identifier(name.clone()),
+            GrammarError::InvalidIdentifier(name.to_string()),
+        ),
+        SyntacticPrimaryKind::TerminalString(text) => (
+            TokenKind::Terminal(text.clone()),
+            GrammarError::InvalidTerminal(text.to_string()),
+        ),
+        SyntacticPrimaryKind::SpecialSequence(text) => (
+            TokenKind::SpecialSeq(text.clone()),
+            GrammarError::InvalidSpecialSequence(text.to_string()),
+        ),
+        _ => return Ok(()),
+    };
+    let text = kind.to_string();
+    let mut lexer = Lexer::with_options(&text, options);
+    match (lexer.next_token(), lexer.next_token()) {
+        (Ok(Some(token)), Ok(None)) if token.kind == expected && token.span.start == 0 => Ok(()),
+        _ => Err(error),
+    }
+}
+
+/// Assigns the spans of the nodes in the `Display` output, where tokens are separated by single
+/// spaces and every rule is on its own line, and validates the leaves.
+///
+/// Like the parser, a node starts where its first token starts and ends where its last token
+/// ends. A node without tokens, like an empty sequence, starts at the next token and ends at the
+/// previous one.
+struct Layout {
+    options: LexerOptions,
+    /// The end of the last token
+    offset: usize,
+    line_start: usize,
+}
+
+impl Layout {
+    /// Where the next token starts
+    fn next_start(&self) -> usize {
+        match self.offset == self.line_start {
+            true => self.offset,
+            false => self.offset + 1,
+        }
+    }
+
+    fn token(&mut self, len: usize) {
+        self.offset = self.next_start() + len;
+    }
+
+    fn newline(&mut self) {
+        self.offset += 1;
+        self.line_start = self.offset;
+    }
+
+    fn rule(&mut self, rule: &mut SyntaxRule) -> Result<(), GrammarError> {
+        let start = self.next_start();
+        self.token(rule.name.len());
+        self.token("=".len());
+        self.definitions(&mut rule.definitions)?;
+        self.token(";".len());
+        rule.span = Span::new(start, self.offset);
+        Ok(())
+    }
+
+    fn definitions(&mut self, definitions: &mut [SingleDefinition]) -> Result<(), GrammarError> {
+        for (index, definition) in definitions.iter_mut().enumerate() {
+            if index != 0 {
+                self.token("|".len());
+            }
+            let start = self.next_start();
+            for (index, term) in definition.terms.iter_mut().enumerate() {
+                if index != 0 {
+                    self.token(",".len());
+                }
+                self.term(term)?;
+            }
+            definition.span = Span::new(start, self.offset);
+        }
+        Ok(())
+    }
+
+    fn term(&mut self, term: &mut SyntacticTerm) -> Result<(), GrammarError> {
+        let start = self.next_start();
+        self.factor(&mut term.factor)?;
+        if let Some(exception) = &mut term.exception {
+            self.token("-".len());
+            self.factor(exception)?;
+        }
+        term.span = Span::new(start, self.offset);
+        Ok(())
+    }
+
+    fn factor(&mut self, factor: &mut SyntacticFactor) -> Result<(), GrammarError> {
+        let start = self.next_start();
+        if let Some(repetition) = factor.repetition {
+            self.token(repetition.to_string().len());
+            self.token("*".len());
+        }
+        let primary = &mut factor.primary;
+        let primary_start = self.next_start();
+        match &mut primary.kind {
+            SyntacticPrimaryKind::OptionalSequence(definitions)
+            | SyntacticPrimaryKind::RepeatedSequence(definitions)
+            | SyntacticPrimaryKind::GroupedSequence(definitions) => {
+                self.token("(".len());
+                self.definitions(definitions)?;
+                self.token(")".len());
+            }
+            SyntacticPrimaryKind::EmptySequence => {}
+            kind => {
+                check(kind, self.options)?;
+                self.token(kind.to_string().len());
+            }
+        }
+        primary.span = Span::new(primary_start, self.offset);
+        factor.span = Span::new(start, self.offset);
+        Ok(())
+    }
+}
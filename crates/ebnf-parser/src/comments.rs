@@ -0,0 +1,148 @@
+//! Attaching comments to the rules and terms they belong to.
+
+use std::collections::HashMap;
+
+use crate::{
+    ast::*,
+    span::Span,
+    visit::{walk_syntactic_term, Visitor},
+    ParseResult,
+};
+
+/// The comments attached to one node
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NodeComments<'a, 'src> {
+    /// Comments on the lines before the node
+    pub leading: Vec<&'a Comment<'src>>,
+    /// Comments after the node on the same line as its end
+    pub trailing: Vec<&'a Comment<'src>>,
+}
+
+/// Comments of a [`ParseResult`] attached to the nearest [`SyntaxRule`] or [`SyntacticTerm`].
+///
+/// A comment which starts on the same line as the end of a node is a trailing comment of the
+/// innermost such node. Other comments are leading comments of the outermost node starting after
+/// them. Comments which fit neither, like those before a `|` on their own line, are trailing
+/// comments of the node before them, or dangling if there is none.
+#[derive(Debug, Clone)]
+pub struct AttachedComments<'a, 'src> {
+    text: &'a str,
+    /// Keyed by the span of the node
+    nodes: HashMap<Span, NodeComments<'a, 'src>>,
+    dangling: Vec<&'a Comment<'src>>,
+}
+
+impl<'a, 'src> AttachedComments<'a, 'src> {
+    /// Attaches the comments of the parse result, which must have been parsed from `text`
+    pub fn new(parse_result: &'a ParseResult<'src>, text: &'a str) -> Self {
+        let mut nodes = Nodes::default();
+        for rule in &parse_result.syntax.rules {
+            nodes.add(rule.span);
+            nodes.visit_syntax_rule(rule);
+        }
+
+        let mut attached = Self {
+            text,
+            nodes: HashMap::new(),
+            dangling: vec![],
+        };
+        for (&index, comments) in &parse_result.comments {
+            let prev_end = index
+                .checked_sub(1)
+                .and_then(|index| parse_result.tokens.get(index))
+                .map(|token| token.span.end);
+            let next_start = parse_result.tokens.get(index).map(|token| token.span.start);
+            let before = prev_end.and_then(|end| nodes.ending.get(&end)).copied();
+            let after = next_start
+                .and_then(|start| nodes.starting.get(&start))
+                .copied();
+            for comment in comments {
+                let same_line =
+                    prev_end.is_some_and(|end| !text[end..comment.span.start].contains('\n'));
+                let node = match (before, after) {
+                    (Some(before), _) if same_line => Some((before, false)),
+                    (_, Some(after)) => Some((after, true)),
+                    (Some(before), None) => Some((before, false)),
+                    (None, None) => None,
+                };
+                match node {
+                    Some((span, leading)) => {
+                        let node = attached.nodes.entry(span).or_insert_with(|| NodeComments {
+                            leading: vec![],
+                            trailing: vec![],
+                        });
+                        match leading {
+                            true => node.leading.push(comment),
+                            false => node.trailing.push(comment),
+                        }
+                    }
+                    None => attached.dangling.push(comment),
+                }
+            }
+        }
+        attached
+    }
+
+    pub fn rule(&self, rule: &SyntaxRule) -> Option<&NodeComments<'a, 'src>> {
+        self.nodes.get(&rule.span)
+    }
+
+    pub fn term(&self, term: &SyntacticTerm) -> Option<&NodeComments<'a, 'src>> {
+        self.nodes.get(&term.span)
+    }
+
+    /// Comments which could not be attached to any node, for example in a file without rules
+    pub fn dangling(&self) -> &[&'a Comment<'src>] {
+        &self.dangling
+    }
+
+    /// Returns the documentation of a rule, which are the leading comments directly before it,
+    /// without blank lines in between. The text of each comment is trimmed and they are joined
+    /// by newlines.
+    pub fn doc_comment(&self, rule: &SyntaxRule) -> Option<String> {
+        let leading = &self.rule(rule)?.leading;
+        let mut start = rule.span.start;
+        let mut docs = vec![];
+        for comment in leading.iter().rev() {
+            if is_blank_line_between(&self.text[comment.span.end..start]) {
+                break;
+            }
+            docs.push(comment.text.trim());
+            start = comment.span.start;
+        }
+        docs.reverse();
+        (!docs.is_empty()).then(|| docs.join("\n"))
+    }
+}
+
+/// Returns whether a line of the text, other than the first and last, contains only whitespace
+fn is_blank_line_between(text: &str) -> bool {
+    let mut lines = text.split('\n');
+    lines.next();
+    lines.next_back();
+    lines.any(|line| line.trim().is_empty())
+}
+
+/// Collects where rules and terms start and end
+#[derive(Default)]
+struct Nodes {
+    /// The outermost node starting at each offset
+    starting: HashMap<usize, Span>,
+    /// The innermost node ending at each offset
+    ending: HashMap<usize, Span>,
+}
+
+impl Nodes {
+    /// Adds a node. Nodes must be added from the outside in.
+    fn add(&mut self, span: Span) {
+        self.starting.entry(span.start).or_insert(span);
+        self.ending.insert(span.end, span);
+    }
+}
+
+impl<'ast, 'src> Visitor<'ast, 'src> for Nodes {
+    fn visit_syntactic_term(&mut self, node: &'ast SyntacticTerm<'src>) {
+        self.add(node.span);
+        walk_syntactic_term(self, node);
+    }
+}
@@ -0,0 +1,341 @@
+//! A lossless concrete syntax tree.
+//!
+//! Unlike the [`ast`](crate::ast), the CST keeps every byte of the source, including whitespace
+//! and comments, so printing a [`CstNode`] reproduces the input exactly. Nodes do not store their
+//! position, which allows editing the tree by replacing its children. The position of an element
+//! is the sum of the [`text_len`](CstElement::text_len) of everything before it.
+
+use std::{borrow::Cow, fmt::Display};
+
+use crate::{ast::*, span::Span, ParseResult, TokenKind};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CstNodeKind {
+    Syntax,
+    SyntaxRule,
+    SingleDefinition,
+    SyntacticTerm,
+    SyntacticFactor,
+    /// A factor which is used as the exception of a term
+    SyntacticException,
+    OptionalSequence,
+    RepeatedSequence,
+    GroupedSequence,
+    MetaIdentifier,
+    TerminalString,
+    SpecialSequence,
+    EmptySequence,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CstTokenKind {
+    Whitespace,
+    Comment,
+    /// Text which is not part of any token, only present after error recovery
+    Error,
+    Identifier,
+    Terminal,
+    SpecialSeq,
+    Integer,
+    LBrace,
+    RBrace,
+    LBracket,
+    RBracket,
+    LParen,
+    RParen,
+    Pipe,
+    Comma,
+    Semicolon,
+    Equal,
+    Star,
+    Dash,
+}
+
+impl CstTokenKind {
+    /// Whether this token only separates other tokens
+    pub fn is_trivia(self) -> bool {
+        matches!(
+            self,
+            CstTokenKind::Whitespace | CstTokenKind::Comment | CstTokenKind::Error
+        )
+    }
+}
+
+impl From<&TokenKind<'_>> for CstTokenKind {
+    fn from(kind: &TokenKind) -> Self {
+        match kind {
+            TokenKind::Identifier(_) => CstTokenKind::Identifier,
+            TokenKind::Terminal(_) => CstTokenKind::Terminal,
+            TokenKind::Comment(_) => CstTokenKind::Comment,
+            TokenKind::SpecialSeq(_) => CstTokenKind::SpecialSeq,
+            TokenKind::Integer(_) => CstTokenKind::Integer,
+            TokenKind::Whitespace(_) => CstTokenKind::Whitespace,
+            TokenKind::Error(_) => CstTokenKind::Error,
+            TokenKind::LBrace => CstTokenKind::LBrace,
+            TokenKind::RBrace => CstTokenKind::RBrace,
+            TokenKind::LBracket => CstTokenKind::LBracket,
+            TokenKind::RBracket => CstTokenKind::RBracket,
+            TokenKind::LParen => CstTokenKind::LParen,
+            TokenKind::RParen => CstTokenKind::RParen,
+            TokenKind::Pipe => CstTokenKind::Pipe,
+            TokenKind::Comma => CstTokenKind::Comma,
+            TokenKind::Semicolon => CstTokenKind::Semicolon,
+            TokenKind::Equal => CstTokenKind::Equal,
+            TokenKind::Star => CstTokenKind::Star,
+            TokenKind::Dash => CstTokenKind::Dash,
+        }
+    }
+}
+
+/// A leaf of the tree with its exact source text, including delimiters like quotes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CstToken<'src> {
+    pub kind: CstTokenKind,
+    pub text: Cow<'src, str>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CstNode<'src> {
+    pub kind: CstNodeKind,
+    pub children: Vec<CstElement<'src>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CstElement<'src> {
+    Node(CstNode<'src>),
+    Token(CstToken<'src>),
+}
+
+impl<'src> CstNode<'src> {
+    /// Builds the tree for the given parse result and the text it was parsed from
+    pub fn new(parse_result: &ParseResult<'src>, text: &'src str) -> Self {
+        let mut pieces: Vec<(CstTokenKind, Span)> = parse_result
+            .tokens
+            .iter()
+            .map(|token| ((&token.kind).into(), token.span))
+            .chain(
+                parse_result
+                    .comments
+                    .values()
+                    .flatten()
+                    .map(|comment| (CstTokenKind::Comment, comment.span)),
+            )
+            .collect();
+        pieces.sort_by_key(|(_, span)| span.start);
+
+        // Fill the gaps between tokens
+        let mut all_pieces = vec![];
+        let mut index = 0;
+        for (kind, span) in pieces {
+            if span.start > index {
+                all_pieces.push(gap(text, index, span.start));
+            }
+            all_pieces.push((kind, span));
+            index = span.end;
+        }
+        if index < text.len() {
+            all_pieces.push(gap(text, index, text.len()));
+        }
+
+        let mut builder = Builder {
+            text,
+            pieces: all_pieces,
+            index: 0,
+        };
+        let mut children = vec![];
+        for rule in &parse_result.syntax.rules {
+            builder.child(&mut children, rule.span, |builder| builder.rule(rule));
+        }
+        builder.take_until(text.len(), &mut children);
+        CstNode {
+            kind: CstNodeKind::Syntax,
+            children,
+        }
+    }
+
+    pub fn text_len(&self) -> usize {
+        self.children.iter().map(CstElement::text_len).sum()
+    }
+
+    /// Returns the child nodes, skipping tokens
+    pub fn child_nodes(&self) -> impl Iterator<Item = &CstNode<'src>> {
+        self.children.iter().filter_map(|child| match child {
+            CstElement::Node(node) => Some(node),
+            CstElement::Token(_) => None,
+        })
+    }
+
+    /// Returns all tokens in this node and its descendants in source order
+    pub fn tokens(&self) -> Vec<&CstToken<'src>> {
+        let mut tokens = vec![];
+        self.collect_tokens(&mut tokens);
+        tokens
+    }
+
+    fn collect_tokens<'a>(&'a self, tokens: &mut Vec<&'a CstToken<'src>>) {
+        for child in &self.children {
+            match child {
+                CstElement::Node(node) => node.collect_tokens(tokens),
+                CstElement::Token(token) => tokens.push(token),
+            }
+        }
+    }
+
+    /// Copies all borrowed text, so the tree no longer depends on the source
+    pub fn into_owned(self) -> CstNode<'static> {
+        CstNode {
+            kind: self.kind,
+            children: self
+                .children
+                .into_iter()
+                .map(CstElement::into_owned)
+                .collect(),
+        }
+    }
+}
+
+impl Display for CstNode<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for child in &self.children {
+            match child {
+                CstElement::Node(node) => write!(f, "{node}")?,
+                CstElement::Token(token) => write!(f, "{}", token.text)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl CstElement<'_> {
+    pub fn text_len(&self) -> usize {
+        match self {
+            CstElement::Node(node) => node.text_len(),
+            CstElement::Token(token) => token.text.len(),
+        }
+    }
+
+    pub fn into_owned(self) -> CstElement<'static> {
+        match self {
+            CstElement::Node(node) => CstElement::Node(node.into_owned()),
+            CstElement::Token(token) => CstElement::Token(CstToken {
+                kind: token.kind,
+                text: Cow::Owned(token.text.into_owned()),
+            }),
+        }
+    }
+}
+
+fn gap(text: &str, start: usize, end: usize) -> (CstTokenKind, Span) {
+    let kind = match text[start..end].trim().is_empty() {
+        true => CstTokenKind::Whitespace,
+        false => CstTokenKind::Error,
+    };
+    (kind, Span::new(start, end))
+}
+
+struct Builder<'src> {
+    text: &'src str,
+    /// Tokens, comments and the text between them, covering the whole source
+    pieces: Vec<(CstTokenKind, Span)>,
+    index: usize,
+}
+
+impl<'src> Builder<'src> {
+    /// Adds all pieces starting before `end` to `children`
+    fn take_until(&mut self, end: usize, children: &mut Vec<CstElement<'src>>) {
+        while let Some((kind, span)) = self.pieces.get(self.index) {
+            if span.start >= end {
+                break;
+            }
+            children.push(CstElement::Token(CstToken {
+                kind: *kind,
+                text: Cow::Borrowed(span.slice(self.text)),
+            }));
+            self.index += 1;
+        }
+    }
+
+    /// Adds the pieces before `span`, then the node built by `build`, which must end at the end of
+    /// `span`
+    fn child(
+        &mut self,
+        children: &mut Vec<CstElement<'src>>,
+        span: Span,
+        build: impl FnOnce(&mut Self) -> (CstNodeKind, Vec<CstElement<'src>>),
+    ) {
+        self.take_until(span.start, children);
+        let (kind, mut node_children) = build(self);
+        self.take_until(span.end, &mut node_children);
+        children.push(CstElement::Node(CstNode {
+            kind,
+            children: node_children,
+        }));
+    }
+
+    fn rule(&mut self, rule: &SyntaxRule) -> (CstNodeKind, Vec<CstElement<'src>>) {
+        let mut children = vec![];
+        self.definitions(&rule.definitions, &mut children);
+        (CstNodeKind::SyntaxRule, children)
+    }
+
+    fn definitions(
+        &mut self,
+        definitions: &[SingleDefinition],
+        children: &mut Vec<CstElement<'src>>,
+    ) {
+        for definition in definitions {
+            self.child(children, definition.span, |builder| {
+                let mut children = vec![];
+                for term in &definition.terms {
+                    builder.child(&mut children, term.span, |builder| builder.term(term));
+                }
+                (CstNodeKind::SingleDefinition, children)
+            });
+        }
+    }
+
+    fn term(&mut self, term: &SyntacticTerm) -> (CstNodeKind, Vec<CstElement<'src>>) {
+        let mut children = vec![];
+        self.child(&mut children, term.factor.span, |builder| {
+            builder.factor(&term.factor, CstNodeKind::SyntacticFactor)
+        });
+        if let Some(exception) = &term.exception {
+            self.child(&mut children, exception.span, |builder| {
+                builder.factor(exception, CstNodeKind::SyntacticException)
+            });
+        }
+        (CstNodeKind::SyntacticTerm, children)
+    }
+
+    fn factor(
+        &mut self,
+        factor: &SyntacticFactor,
+        kind: CstNodeKind,
+    ) -> (CstNodeKind, Vec<CstElement<'src>>) {
+        let mut children = vec![];
+        let primary = &factor.primary;
+        self.child(&mut children, primary.span, |builder| {
+            let mut children = vec![];
+            let kind = match &primary.kind {
+                SyntacticPrimaryKind::OptionalSequence(definitions) => {
+                    builder.definitions(definitions, &mut children);
+                    CstNodeKind::OptionalSequence
+                }
+                SyntacticPrimaryKind::RepeatedSequence(definitions) => {
+                    builder.definitions(definitions, &mut children);
+                    CstNodeKind::RepeatedSequence
+                }
+                SyntacticPrimaryKind::GroupedSequence(definitions) => {
+                    builder.definitions(definitions, &mut children);
+                    CstNodeKind::GroupedSequence
+                }
+                SyntacticPrimaryKind::MetaIdentifier(_) => CstNodeKind::MetaIdentifier,
+                SyntacticPrimaryKind::TerminalString(_) => CstNodeKind::TerminalString,
+                SyntacticPrimaryKind::SpecialSequence(_) => CstNodeKind::SpecialSequence,
+                SyntacticPrimaryKind::EmptySequence => CstNodeKind::EmptySequence,
+            };
+            (kind, children)
+        });
+        (kind, children)
+    }
+}
@@ -0,0 +1,261 @@
+use std::{fmt::Write, iter};
+
+use unicode_width::UnicodeWidthChar;
+
+use crate::{
+    error::SyntaxError,
+    source_map::{ColumnUnit, SourceMap},
+    span::Span,
+};
+
+/// How many lines of a multi-line span are shown before the middle is elided
+const MAX_SPAN_LINES: usize = 6;
+const TAB_WIDTH: usize = 4;
+
+const BOLD: &str = "\x1b[1m";
+const BLUE: &str = "\x1b[1;34m";
+const GREEN: &str = "\x1b[1;32m";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+impl Severity {
+    fn name(self) -> &'static str {
+        match self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        }
+    }
+
+    fn color(self) -> &'static str {
+        match self {
+            Severity::Error => "\x1b[1;31m",
+            Severity::Warning => "\x1b[1;33m",
+        }
+    }
+}
+
+/// A message about a location in a source text which can be rendered similar to rustc's errors
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub span: Span,
+    pub message: String,
+    /// A short text shown next to the underlined span
+    pub label: Option<String>,
+    pub notes: Vec<String>,
+    pub help: Vec<String>,
+    /// Other locations related to this diagnostic, each shown with its own message
+    pub related: Vec<(Span, String)>,
+}
+
+impl Diagnostic {
+    pub fn new(severity: Severity, span: Span, message: impl Into<String>) -> Self {
+        Self {
+            severity,
+            span,
+            message: message.into(),
+            label: None,
+            notes: vec![],
+            help: vec![],
+            related: vec![],
+        }
+    }
+
+    pub fn error(span: Span, message: impl Into<String>) -> Self {
+        Self::new(Severity::Error, span, message)
+    }
+
+    pub fn warning(span: Span, message: impl Into<String>) -> Self {
+        Self::new(Severity::Warning, span, message)
+    }
+
+    pub fn with_label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    pub fn with_note(mut self, note: impl Into<String>) -> Self {
+        self.notes.push(note.into());
+        self
+    }
+
+    pub fn with_help(mut self, help: impl Into<String>) -> Self {
+        self.help.push(help.into());
+        self
+    }
+
+    pub fn with_related(mut self, span: Span, message: impl Into<String>) -> Self {
+        self.related.push((span, message.into()));
+        self
+    }
+
+    /// Renders this diagnostic with the affected source lines and a caret underline, optionally
+    /// using ANSI escape codes for colors. The result does not end with a newline.
+    pub fn render(&self, source_map: &SourceMap, color: bool) -> String {
+        let painter = Painter { color };
+        let accent = self.severity.color();
+        let gutter = " ".repeat(
+            iter::once(self.span)
+                .chain(self.related.iter().map(|(span, _)| *span))
+                .map(|span| snippet_lines(source_map, span).2.to_string().len())
+                .max()
+                .expect("iterator contains at least `self.span`"),
+        );
+
+        let mut out = String::new();
+        let _ = write!(
+            out,
+            "{}{}",
+            painter.paint(accent, self.severity.name()),
+            painter.paint(BOLD, &format!(": {}", self.message)),
+        );
+        render_snippet(
+            &mut out,
+            source_map,
+            &painter,
+            &gutter,
+            self.span,
+            self.label.as_deref(),
+            accent,
+        );
+
+        for (kind, messages) in [("note", &self.notes), ("help", &self.help)] {
+            for message in messages {
+                let _ = write!(
+                    out,
+                    "\n{gutter} {} {}: {message}",
+                    painter.paint(BLUE, "="),
+                    painter.paint(BOLD, kind),
+                );
+            }
+        }
+
+        for (span, message) in &self.related {
+            let _ = write!(
+                out,
+                "\n{}{}",
+                painter.paint(GREEN, "note"),
+                painter.paint(BOLD, &format!(": {message}")),
+            );
+            render_snippet(&mut out, source_map, &painter, &gutter, *span, None, GREEN);
+        }
+
+        // Remove trailing spaces caused by empty source lines
+        out.lines()
+            .map(str::trim_end)
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+struct Painter {
+    color: bool,
+}
+
+impl Painter {
+    fn paint(&self, style: &str, text: &str) -> String {
+        match self.color {
+            true => format!("{style}{text}\x1b[0m"),
+            false => text.to_string(),
+        }
+    }
+}
+
+/// Returns the span clamped to the text and the first and last line to show for it
+fn snippet_lines(source_map: &SourceMap, span: Span) -> (Span, usize, usize) {
+    let text = source_map.text();
+    let start = span.start.min(text.len());
+    let end = span.end.clamp(start, text.len());
+    let (start_pos, end_pos) = source_map
+        .line_index()
+        .span_line_cols(Span::new(start, end), ColumnUnit::Utf8);
+    // Don't show the next line when the span ends with a line break
+    let end_line = match end > start && end_pos.col == 1 && end_pos.line > start_pos.line {
+        true => end_pos.line - 1,
+        false => end_pos.line,
+    };
+    (Span::new(start, end), start_pos.line, end_line)
+}
+
+/// Writes the location of `span` followed by the affected source lines with an underline
+fn render_snippet(
+    out: &mut String,
+    source_map: &SourceMap,
+    painter: &Painter,
+    gutter: &str,
+    span: Span,
+    label: Option<&str>,
+    accent: &str,
+) {
+    let text = source_map.text();
+    let index = source_map.line_index();
+    let (Span { start, end }, start_line, end_line) = snippet_lines(source_map, span);
+
+    let _ = write!(
+        out,
+        "\n{gutter}{} {}",
+        painter.paint(BLUE, "-->"),
+        source_map.location(span),
+    );
+    let _ = write!(out, "\n{gutter} {}", painter.paint(BLUE, "|"));
+
+    let line_count = end_line - start_line + 1;
+    for line in start_line..=end_line {
+        let line_number = line - start_line;
+        if line_count > MAX_SPAN_LINES
+            && line_number >= MAX_SPAN_LINES / 2
+            && line_number < line_count - MAX_SPAN_LINES / 2
+        {
+            if line_number == MAX_SPAN_LINES / 2 {
+                let _ = write!(out, "\n{}", painter.paint(BLUE, "..."));
+            }
+            continue;
+        }
+
+        let line_span = index
+            .line_span(line)
+            .expect("line is between the lines of `start` and `end`");
+        let underline_start = start.max(line_span.start);
+        let underline_end = end.min(line_span.end).max(underline_start);
+        let padding = display_width(&text[line_span.start..underline_start]);
+        let carets = display_width(&text[underline_start..underline_end]).max(1);
+
+        let _ = write!(
+            out,
+            "\n{} {} {}",
+            painter.paint(BLUE, &format!("{line:>width$}", width = gutter.len())),
+            painter.paint(BLUE, "|"),
+            text[line_span.start..line_span.end].replace('\t', &" ".repeat(TAB_WIDTH)),
+        );
+        let _ = write!(
+            out,
+            "\n{gutter} {} {}{}",
+            painter.paint(BLUE, "|"),
+            " ".repeat(padding),
+            painter.paint(accent, &"^".repeat(carets)),
+        );
+        if let (Some(label), true) = (label, line == end_line) {
+            let _ = write!(out, " {}", painter.paint(accent, label));
+        }
+    }
+}
+
+impl From<SyntaxError> for Diagnostic {
+    fn from(err: SyntaxError) -> Self {
+        Self::error(err.span, err.message)
+    }
+}
+
+/// The number of terminal columns of `text`, counting wide characters like `数` as two
+fn display_width(text: &str) -> usize {
+    text.chars()
+        .map(|c| match c {
+            '\t' => TAB_WIDTH,
+            c => c.width().unwrap_or(0),
+        })
+        .sum()
+}
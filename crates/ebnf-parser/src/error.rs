@@ -1,8 +1,8 @@
-use std::borrow::Cow;
+use std::{borrow::Cow, fmt::Display};
 
 use thiserror::Error;
 
-use crate::span::Span;
+use crate::{diagnostic::Diagnostic, source_map::SourceMap, span::Span};
 
 #[derive(Debug, Clone, Error)]
 #[error("invalid syntax at {span}: {message}")]
@@ -18,4 +18,49 @@ impl SyntaxError {
             message: message.into_owned(),
         }
     }
+
+    /// Returns a [`Display`] implementation that prints this error as `name:line:col: message`
+    pub fn display<'a>(&'a self, source_map: &'a SourceMap<'a>) -> impl Display + 'a {
+        LocatedSyntaxError {
+            error: self,
+            source_map,
+        }
+    }
+
+    /// Renders this error like rustc does, see [`Diagnostic::render`]
+    pub fn render(&self, source_map: &SourceMap, color: bool) -> String {
+        Diagnostic::from(self.clone()).render(source_map, color)
+    }
+}
+
+struct LocatedSyntaxError<'a> {
+    error: &'a SyntaxError,
+    source_map: &'a SourceMap<'a>,
+}
+
+impl Display for LocatedSyntaxError<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}: {}",
+            self.source_map.location(self.error.span),
+            self.error.message,
+        )
+    }
+}
+
+/// A [`Syntax`](crate::ast::Syntax) which cannot be written in EBNF, returned by
+/// [`Grammar::build`](crate::builder::Grammar::build) and [`validate`](crate::builder::validate)
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum GrammarError {
+    #[error("the grammar has no rules")]
+    Empty,
+    #[error("rule '{0}' is defined more than once")]
+    DuplicateRule(String),
+    #[error("'{0}' is not a valid meta identifier")]
+    InvalidIdentifier(String),
+    #[error("terminal string {0:?} cannot be written in EBNF")]
+    InvalidTerminal(String),
+    #[error("special sequence {0:?} cannot be written in EBNF")]
+    InvalidSpecialSequence(String),
 }
@@ -1,4 +1,4 @@
-use std::{mem, str::Chars};
+use std::{borrow::Cow, mem, str::Chars};
 
 use crate::{
     error::SyntaxError,
@@ -7,36 +7,63 @@ use crate::{
 };
 
 macro_rules! simple_token {
-    ($self:ident, $token:expr) => {{
+    ($self:ident, $token:expr) => {
+        simple_token!($self, $token, 1)
+    };
+    ($self:ident, $token:expr, $len:literal) => {{
         let start = $self.index;
-        $self.next();
+        for _ in 0..$len {
+            $self.bump();
+        }
         Ok(Token::new($token, Span::new(start, $self.index)))
     }};
 }
 
+/// Options to enable optional features of the [`Lexer`]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LexerOptions {
+    /// Join words separated by spaces or tabs into one meta identifier, as allowed by ISO 14977.
+    /// The resulting name has the words separated by exactly one space.
+    pub spaced_identifiers: bool,
+    /// Emit runs of whitespace as [`TokenKind::Whitespace`] tokens instead of skipping them, so
+    /// that the tokens cover the whole text. Comments are always emitted.
+    pub trivia: bool,
+    /// Accept meta identifiers made of Unicode letters and digits, like `größe` or `数字`. They
+    /// must start with a character with the `XID_Start` property, followed by characters with
+    /// the `XID_Continue` property. By default, only ASCII letters, digits and `_` are allowed.
+    pub unicode_identifiers: bool,
+}
+
+#[derive(Clone)]
 pub struct Lexer<'src> {
     text: &'src str,
     src: Chars<'src>,
     curr_char: Option<char>,
     next_char: Option<char>,
     pub(crate) index: usize,
+    options: LexerOptions,
 }
 
 impl<'src> Lexer<'src> {
     pub fn new(text: &'src str) -> Self {
+        Self::with_options(text, LexerOptions::default())
+    }
+
+    pub fn with_options(text: &'src str, options: LexerOptions) -> Self {
         let mut lexer = Lexer {
             text,
             src: text.chars(),
             curr_char: None,
             next_char: None,
             index: 0,
+            options,
         };
-        lexer.next();
-        lexer.next();
+        lexer.bump();
+        lexer.bump();
         lexer
     }
 
-    fn next(&mut self) {
+    fn bump(&mut self) {
         if let Some(curr_char) = self.curr_char {
             self.index += curr_char.len_utf8();
         }
@@ -44,9 +71,20 @@ impl<'src> Lexer<'src> {
         self.next_char = self.src.next();
     }
 
+    /// Returns the next token, or `None` at the end of the text.
+    ///
+    /// Illegal characters are returned as [`TokenKind::Error`] tokens. An unterminated comment,
+    /// terminal string or special sequence is an error, after which the lexer is at the end.
     pub fn next_token(&mut self) -> Result<Option<Token<'src>>, SyntaxError> {
+        let whitespace_start = self.index;
         while let Some(' ' | '\n' | '\t' | '\r') = self.curr_char {
-            self.next();
+            self.bump();
+        }
+        if self.options.trivia && self.index != whitespace_start {
+            return Ok(Some(Token::new(
+                TokenKind::Whitespace(Cow::Borrowed(&self.text[whitespace_start..self.index])),
+                Span::new(whitespace_start, self.index),
+            )));
         }
         if let Some(curr_char) = self.curr_char {
             let token_result = match curr_char {
@@ -54,6 +92,13 @@ impl<'src> Lexer<'src> {
                 '}' => simple_token!(self, TokenKind::RBrace),
                 '[' => simple_token!(self, TokenKind::LBracket),
                 ']' => simple_token!(self, TokenKind::RBracket),
+                // Alternative representations as defined in ISO 14977 §7.x
+                '(' if self.next_char == Some(':') => simple_token!(self, TokenKind::LBrace, 2),
+                ':' if self.next_char == Some(')') => simple_token!(self, TokenKind::RBrace, 2),
+                '(' if self.next_char == Some('/') => simple_token!(self, TokenKind::LBracket, 2),
+                '/' if self.next_char == Some(')') => simple_token!(self, TokenKind::RBracket, 2),
+                '/' | '!' => simple_token!(self, TokenKind::Pipe),
+                '.' => simple_token!(self, TokenKind::Semicolon),
                 '(' if self.next_char != Some('*') => simple_token!(self, TokenKind::LParen),
                 ')' => simple_token!(self, TokenKind::RParen),
                 '|' => simple_token!(self, TokenKind::Pipe),
@@ -65,14 +110,14 @@ impl<'src> Lexer<'src> {
                 '(' => self.parse_comment(),
                 '\'' | '"' => self.parse_terminal(),
                 '?' => self.parse_special_seq(),
-                c if c.is_ascii_alphabetic() => self.parse_identifier(),
+                c if self.is_identifier_start(c) => self.parse_identifier(),
                 c if c.is_ascii_digit() => self.parse_integer(),
-                c => {
+                _ => {
                     let span_start = self.index;
-                    self.next();
-                    Err(SyntaxError::new(
+                    self.bump();
+                    Ok(Token::new(
+                        TokenKind::Error(Cow::Borrowed(&self.text[span_start..self.index])),
                         Span::new(span_start, self.index),
-                        format!("Illegal character '{}'", c).into(),
                     ))
                 }
             };
@@ -85,15 +130,26 @@ impl<'src> Lexer<'src> {
         }
     }
 
-    fn delimeted_str(&mut self, delimeter: Option<char>) -> &'src str {
-        self.next(); // opening delimeter
+    fn delimeted_str(&mut self, delimeter: char, name: &str) -> Result<&'src str, SyntaxError> {
+        let span_start = self.index;
+        self.bump(); // opening delimeter
         let content_start = self.index;
-        while self.curr_char.is_some() && self.curr_char != delimeter {
-            self.next();
+        while self.curr_char.is_some() && self.curr_char != Some(delimeter) {
+            self.bump();
+        }
+        if self.curr_char.is_none() {
+            return Err(SyntaxError::new(
+                Span::new(span_start, content_start),
+                format!(
+                    "Unterminated {name}, expected closing {}",
+                    quoted(&delimeter.to_string()),
+                )
+                .into(),
+            ));
         }
         let content_end = self.index;
-        self.next(); // closing delimeter
-        &self.text[content_start..content_end]
+        self.bump(); // closing delimeter
+        Ok(&self.text[content_start..content_end])
     }
 
     fn parse_comment(&mut self) -> Result<Token<'src>, SyntaxError> {
@@ -106,22 +162,40 @@ impl<'src> Lexer<'src> {
 
         let span_start = self.index;
 
-        self.next();
-        self.next();
+        self.bump();
+        self.bump();
         let content_start = self.index;
-        while self.curr_char.is_some()
-            && !(self.curr_char == Some('*') && self.next_char == Some(')'))
-        {
-            self.next();
+        // Comments may be nested, so count the currently open ones
+        let mut depth = 1;
+        while let Some(curr_char) = self.curr_char {
+            match (curr_char, self.next_char) {
+                ('(', Some('*')) => {
+                    depth += 1;
+                    self.bump();
+                }
+                ('*', Some(')')) if depth == 1 => break,
+                ('*', Some(')')) => {
+                    depth -= 1;
+                    self.bump();
+                }
+                _ => {}
+            }
+            self.bump();
+        }
+        if self.curr_char.is_none() {
+            return Err(SyntaxError::new(
+                Span::new(span_start, content_start),
+                format!("Unterminated comment, expected closing {}", quoted("*)")).into(),
+            ));
         }
         let content_end = self.index;
-        self.next();
-        self.next();
+        self.bump();
+        self.bump();
 
         let content = &self.text[content_start..content_end];
 
         Ok(Token::new(
-            TokenKind::Comment(content),
+            TokenKind::Comment(Cow::Borrowed(content)),
             Span::new(span_start, self.index),
         ))
     }
@@ -133,12 +207,14 @@ impl<'src> Lexer<'src> {
             self.curr_char,
         );
 
-        let quote = self.curr_char;
+        let quote = self
+            .curr_char
+            .expect("`parse_terminal` is only called when `curr_char` is a quote");
         let span_start = self.index;
-        let content = self.delimeted_str(quote).trim();
+        let content = self.delimeted_str(quote, "terminal string")?.trim();
 
         Ok(Token::new(
-            TokenKind::Terminal(content),
+            TokenKind::Terminal(Cow::Borrowed(content)),
             Span::new(span_start, self.index),
         ))
     }
@@ -151,51 +227,83 @@ impl<'src> Lexer<'src> {
         );
 
         let span_start = self.index;
-        let content = self.delimeted_str(Some('?')).trim();
+        let content = self.delimeted_str('?', "special sequence")?.trim();
 
         Ok(Token::new(
-            TokenKind::SpecialSeq(content),
+            TokenKind::SpecialSeq(Cow::Borrowed(content)),
             Span::new(span_start, self.index),
         ))
     }
 
     fn parse_identifier(&mut self) -> Result<Token<'src>, SyntaxError> {
         debug_assert!(
-            self.curr_char.map_or(false, |c| c.is_ascii_alphabetic()),
+            self.curr_char.is_some_and(|c| self.is_identifier_start(c)),
             "Expected letter, was {:?}",
             self.curr_char,
         );
 
         let span_start = self.index;
-        let content_start = self.index;
-        self.next(); // first letter
-        while self
-            .curr_char
-            .map_or(false, |c| c.is_ascii_alphanumeric() || c == '_')
-        {
-            self.next();
+        let mut words = vec![];
+        let mut single_spaced = true;
+        loop {
+            let word_start = self.index;
+            self.bump(); // first letter
+            while self
+                .curr_char
+                .is_some_and(|c| self.is_identifier_continue(c))
+            {
+                self.bump();
+            }
+            words.push(&self.text[word_start..self.index]);
+
+            if !self.options.spaced_identifiers {
+                break;
+            }
+            let rest = &self.text[self.index..];
+            let gap = rest.len() - rest.trim_start_matches([' ', '\t']).len();
+            if gap == 0 || !rest[gap..].starts_with(|c| self.is_identifier_start(c)) {
+                break;
+            }
+            single_spaced &= &rest[..gap] == " ";
+            for _ in 0..gap {
+                self.bump();
+            }
         }
-        let content_end = self.index;
-        let content = &self.text[content_start..content_end];
+
+        let name = match single_spaced {
+            true => Cow::Borrowed(&self.text[span_start..self.index]),
+            false => Cow::Owned(words.join(" ")),
+        };
 
         Ok(Token::new(
-            TokenKind::Identifier(content),
+            TokenKind::Identifier(name),
             Span::new(span_start, self.index),
         ))
     }
 
+    fn is_identifier_start(&self, c: char) -> bool {
+        c.is_ascii_alphabetic()
+            || self.options.unicode_identifiers && unicode_ident::is_xid_start(c)
+    }
+
+    fn is_identifier_continue(&self, c: char) -> bool {
+        c.is_ascii_alphanumeric()
+            || c == '_'
+            || self.options.unicode_identifiers && unicode_ident::is_xid_continue(c)
+    }
+
     fn parse_integer(&mut self) -> Result<Token<'src>, SyntaxError> {
         debug_assert!(
-            self.curr_char.map_or(false, |c| c.is_ascii_digit()),
+            self.curr_char.is_some_and(|c| c.is_ascii_digit()),
             "Expected digit, was {:?}",
             self.curr_char,
         );
 
         let span_start = self.index;
         let content_start = self.index;
-        self.next(); // first digit
-        while self.curr_char.map_or(false, |c| c.is_ascii_digit()) {
-            self.next();
+        self.bump(); // first digit
+        while self.curr_char.is_some_and(|c| c.is_ascii_digit()) {
+            self.bump();
         }
         let content_end = self.index;
         let slice = &self.text[content_start..content_end];
@@ -215,3 +323,20 @@ impl<'src> Lexer<'src> {
         ))
     }
 }
+
+/// Yields the tokens of [`Lexer::next_token`] until the end of the text
+impl<'src> Iterator for Lexer<'src> {
+    type Item = Result<Token<'src>, SyntaxError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_token().transpose()
+    }
+}
+
+/// Surrounds `text` with quotes that do not occur in it
+fn quoted(text: &str) -> String {
+    match text.contains('\'') {
+        true => format!("\"{text}\""),
+        false => format!("'{text}'"),
+    }
+}
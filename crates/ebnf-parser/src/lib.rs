@@ -1,17 +1,30 @@
+pub mod analysis;
 pub mod ast;
+pub mod builder;
+pub mod comments;
+pub mod cst;
+pub mod diagnostic;
 pub mod error;
 mod lexer;
 mod parser;
+pub mod source_map;
 pub mod span;
 mod token;
+pub mod visit;
 
-pub use lexer::Lexer;
+pub use lexer::{Lexer, LexerOptions};
 pub use parser::*;
 pub use token::*;
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{
+        ast::SyntacticPrimaryKind,
+        diagnostic::Diagnostic,
+        source_map::{ColumnUnit, LineCol, LineIndex, SourceMap},
+        span::Span,
+    };
 
     #[test]
     fn lex() {
@@ -22,6 +35,381 @@ mod tests {
         }
     }
 
+    #[test]
+    fn lex_unterminated() {
+        for (text, span, message) in [
+            (
+                "a = 'abc",
+                Span::new(4, 5),
+                "Unterminated terminal string, expected closing \"'\"",
+            ),
+            (
+                "a = \"abc ;",
+                Span::new(4, 5),
+                "Unterminated terminal string, expected closing '\"'",
+            ),
+            (
+                "a = ? abc ;",
+                Span::new(4, 5),
+                "Unterminated special sequence, expected closing '?'",
+            ),
+            (
+                "a = b ; (* abc *",
+                Span::new(8, 10),
+                "Unterminated comment, expected closing '*)'",
+            ),
+        ] {
+            let err = Parser::new(Lexer::new(text)).parse().unwrap_err();
+            assert_eq!(err.span, span, "{text}");
+            assert_eq!(err.message, message, "{text}");
+        }
+    }
+
+    #[test]
+    fn lex_nested_comment() {
+        let mut lexer = Lexer::new("(* outer (* inner *) still outer *) a");
+        let token = lexer.next_token().unwrap().unwrap();
+        assert_eq!(
+            token.kind,
+            TokenKind::Comment(" outer (* inner *) still outer ".into())
+        );
+        assert_eq!(token.span, Span::new(0, 35));
+        assert_eq!(
+            lexer.next_token().unwrap().unwrap().kind,
+            TokenKind::Identifier("a".into())
+        );
+    }
+
+    #[test]
+    fn line_index() {
+        let text = "a = 'ä' ;\r\nb = '𝄞' , c ) ;\n";
+        let index = LineIndex::new(text);
+        assert_eq!(index.line_count(), 3);
+        assert_eq!(index.line_span(1), Some(Span::new(0, 10)));
+        assert_eq!(index.line_span(3), Some(Span::new(31, 31)));
+        assert_eq!(index.line_span(4), None);
+
+        let c = text.find('c').unwrap();
+        assert_eq!(
+            index.line_col(c, ColumnUnit::Utf8),
+            LineCol { line: 2, col: 14 }
+        );
+        assert_eq!(
+            index.line_col(c, ColumnUnit::Utf16),
+            LineCol { line: 2, col: 12 }
+        );
+        for unit in [ColumnUnit::Utf8, ColumnUnit::Utf16] {
+            assert_eq!(index.offset(index.line_col(c, unit), unit), Some(c));
+        }
+        assert_eq!(
+            index.offset(LineCol { line: 2, col: 7 }, ColumnUnit::Utf16),
+            None
+        );
+
+        let err = Parser::new(Lexer::new(text)).parse().unwrap_err();
+        assert_eq!(
+            err.display(&SourceMap::new("test.ebnf", text)).to_string(),
+            "test.ebnf:2:16: Expected ';', was ')'",
+        );
+    }
+
+    #[test]
+    fn render_diagnostic() {
+        let text = "a = b ;\r\nc = (\td\r\n  | e ;\r\n";
+        let source_map = SourceMap::new("test.ebnf", text);
+        let err = Parser::new(Lexer::new(text)).parse().unwrap_err();
+        assert_eq!(
+            err.render(&source_map, false),
+            "error: Expected ')', was ';'
+ --> test.ebnf:3:7
+  |
+3 |   | e ;
+  |       ^",
+        );
+
+        let diagnostic = Diagnostic::error(Span::new(13, 23), "unclosed group")
+            .with_label("group starts here")
+            .with_note("groups must be closed with ')'")
+            .with_help("add ')' before ';'");
+        assert_eq!(
+            diagnostic.render(&source_map, false),
+            "error: unclosed group
+ --> test.ebnf:2:5
+  |
+2 | c = (    d
+  |     ^^^^^^
+3 |   | e ;
+  | ^^^^^ group starts here
+  = note: groups must be closed with ')'
+  = help: add ')' before ';'",
+        );
+
+        let at_eof = Diagnostic::warning(Span::new(text.len(), text.len() + 1), "at end");
+        assert_eq!(
+            at_eof.render(&source_map, false),
+            "warning: at end\n --> test.ebnf:4:1\n  |\n4 |\n  | ^",
+        );
+        assert!(at_eof
+            .render(&source_map, true)
+            .contains("\x1b[1;33mwarning"));
+    }
+
+    #[test]
+    fn check_rules() {
+        let text = "a = b , [ c - d ] ;\nb = 'b' ;\nb = 'c' ;\nd = ( e ) ;\nf = a ;\n";
+        let syntax = Parser::new(Lexer::new(text)).parse().unwrap().syntax;
+        let span_of = |needle: &str| {
+            let start = text.find(needle).unwrap();
+            Span::new(start, start + needle.len())
+        };
+        assert_eq!(
+            analysis::check_rules(&syntax, None),
+            [
+                analysis::RuleIssue::Undefined {
+                    name: "c".into(),
+                    usage: Span::new(10, 11),
+                },
+                analysis::RuleIssue::Duplicate {
+                    name: "b".into(),
+                    definition: span_of("b = 'c' ;"),
+                    first_definition: span_of("b = 'b' ;"),
+                },
+                analysis::RuleIssue::Undefined {
+                    name: "e".into(),
+                    usage: span_of("e"),
+                },
+                analysis::RuleIssue::Unreachable {
+                    name: "f".into(),
+                    definition: span_of("f = a ;"),
+                    start_rule: span_of("a = b , [ c - d ] ;"),
+                },
+            ]
+        );
+        assert_eq!(analysis::check_rules(&syntax, Some("f")).len(), 3);
+    }
+
+    #[test]
+    fn first_follow() {
+        use analysis::{FirstFollow, Terminal, TerminalSet};
+
+        let text = "s = a , 'e' ;\na = [ 'x' ] , b | 2 * c ;\nb = { 'z' } ;\nc = 'y' | ;\n";
+        let syntax = Parser::new(Lexer::new(text)).parse().unwrap().syntax;
+        let sets = FirstFollow::new(&syntax, None);
+        let set =
+            |terminals: &[Terminal<'static>]| terminals.iter().copied().collect::<TerminalSet>();
+
+        for (name, nullable, first, follow) in [
+            (
+                "s",
+                false,
+                set(&[
+                    Terminal::String("e"),
+                    Terminal::String("x"),
+                    Terminal::String("y"),
+                    Terminal::String("z"),
+                ]),
+                set(&[Terminal::End]),
+            ),
+            (
+                "a",
+                true,
+                set(&[
+                    Terminal::String("x"),
+                    Terminal::String("y"),
+                    Terminal::String("z"),
+                ]),
+                set(&[Terminal::String("e")]),
+            ),
+            (
+                "b",
+                true,
+                set(&[Terminal::String("z")]),
+                set(&[Terminal::String("e")]),
+            ),
+            (
+                "c",
+                true,
+                set(&[Terminal::String("y")]),
+                set(&[Terminal::String("e"), Terminal::String("y")]),
+            ),
+        ] {
+            assert_eq!(sets.rule_nullable(name), Some(nullable), "{name}");
+            assert_eq!(sets.rule_first(name), Some(&first), "{name}");
+            assert_eq!(sets.rule_follow(name), Some(&follow), "{name}");
+        }
+
+        let SyntacticPrimaryKind::OptionalSequence(optional) =
+            &syntax.rules[1].definitions[0].terms[0].factor.primary.kind
+        else {
+            panic!("expected optional sequence");
+        };
+        assert!(!sets.nullable(&optional[0]));
+        assert_eq!(sets.first(&optional[..]), set(&[Terminal::String("x")]));
+        assert_eq!(
+            sets.follow(&optional[0]),
+            Some(&set(&[Terminal::String("e"), Terminal::String("z")]))
+        );
+        let repetition = &syntax.rules[1].definitions[1].terms[0].factor;
+        assert_eq!(
+            sets.follow(&repetition.primary),
+            Some(&set(&[Terminal::String("e"), Terminal::String("y")]))
+        );
+    }
+
+    #[test]
+    fn check_ll1() {
+        use analysis::{FirstFollow, Ll1ConflictKind, Terminal};
+
+        let text = "s = a | 'x' , 'y' ;\na = 'x' | [ 'z' ] ;\nb = { 'w' } , 'w' | ;\n";
+        let syntax = Parser::new(Lexer::new(text)).parse().unwrap().syntax;
+        let sets = FirstFollow::new(&syntax, None);
+        let span_of = |needle: &str| {
+            let start = text.find(needle).unwrap();
+            Span::new(start, start + needle.len())
+        };
+        let conflicts: Vec<_> = analysis::check_ll1(&syntax, &sets)
+            .into_iter()
+            .map(|conflict| {
+                (
+                    conflict.rule,
+                    conflict.kind,
+                    conflict.terminals.into_iter().collect::<Vec<_>>(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            conflicts,
+            [
+                (
+                    "s",
+                    Ll1ConflictKind::FirstFirst {
+                        first: Span::new(4, 5),
+                        second: span_of("'x' , 'y'"),
+                    },
+                    vec![Terminal::String("x")],
+                ),
+                (
+                    "b",
+                    Ll1ConflictKind::Entry {
+                        sequence: span_of("{ 'w' }"),
+                    },
+                    vec![Terminal::String("w")],
+                ),
+            ]
+        );
+
+        let text = include_str!("../grammar.ebnf");
+        let syntax = Parser::new(Lexer::new(text)).parse().unwrap().syntax;
+        let sets = FirstFollow::new(&syntax, Some("syntax"));
+        assert_eq!(analysis::check_ll1(&syntax, &sets), []);
+    }
+
+    #[test]
+    fn find_left_recursion() {
+        use analysis::{FirstFollow, LeftRecursionStep};
+
+        let text = "a = [ 'x' ] , b , 'y' ;\nb = c | 'z' ;\nc = { 'w' } , a ;\nd = d , 'q' ;\ne = 'e' , e ;\n";
+        let syntax = Parser::new(Lexer::new(text)).parse().unwrap().syntax;
+        let sets = FirstFollow::new(&syntax, None);
+        let step = |rule, reference: &str| {
+            let rule_start = text.find(&format!("{rule} =")).unwrap();
+            let reference_start = rule_start + text[rule_start..].find(reference).unwrap();
+            LeftRecursionStep {
+                rule,
+                rule_span: Span::new(
+                    rule_start,
+                    rule_start + text[rule_start..].find(';').unwrap() + 1,
+                ),
+                reference: Span::new(
+                    reference_start + reference.len() - 1,
+                    reference_start + reference.len(),
+                ),
+            }
+        };
+        let cycles: Vec<_> = analysis::find_left_recursion(&syntax, &sets)
+            .into_iter()
+            .map(|recursion| recursion.cycle)
+            .collect();
+        assert_eq!(
+            cycles,
+            [
+                vec![step("a", ", b"), step("b", "= c"), step("c", ", a")],
+                vec![step("d", "= d")],
+            ]
+        );
+    }
+
+    #[test]
+    fn find_non_productive() {
+        let text = "a = 'x' , a ;\nb = c , 'y' | ( c ) ;\nc = b ;\nd = 'z' | { d } ;\ne = 'e' - f ;\nf = f ;\n";
+        let syntax = Parser::new(Lexer::new(text)).parse().unwrap().syntax;
+        let rules: Vec<_> = analysis::find_non_productive(&syntax)
+            .into_iter()
+            .map(|rule| {
+                (
+                    rule.rule,
+                    rule.causes
+                        .into_iter()
+                        .map(|(cause, span)| (cause, &text[span.start..span.end]))
+                        .collect::<Vec<_>>(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            rules,
+            [
+                ("a", vec![("a", "a")]),
+                ("b", vec![("c", "c"), ("c", "c")]),
+                ("c", vec![("b", "b")]),
+                ("f", vec![("f", "f")]),
+            ]
+        );
+    }
+
+    #[test]
+    fn check_exceptions() {
+        let text = "a = b - c ;\nb = 'x' | 'y' ;\nc = 'x' | '(' , c , ')' ;\nd = b - ( 'y' | e ) ;\ne = ? letter ? ;\nf = b - g ;\n";
+        let syntax = Parser::new(Lexer::new(text)).parse().unwrap().syntax;
+        let issues: Vec<_> = analysis::check_exceptions(&syntax)
+            .into_iter()
+            .map(|issue| (&text[issue.exception().start..issue.exception().end], issue))
+            .collect();
+        let c = text.find("c ,").unwrap();
+        let letter = text.find("? letter ?").unwrap();
+        let g = text.rfind('g').unwrap();
+        assert_eq!(
+            issues,
+            [
+                (
+                    "c",
+                    analysis::ExceptionIssue::Recursive {
+                        exception: Span::new(8, 9),
+                        rule: "c",
+                        cycle: vec![("c", Span::new(c, c + 1))],
+                    }
+                ),
+                (
+                    "( 'y' | e )",
+                    analysis::ExceptionIssue::SpecialSequence {
+                        exception: Span::new(
+                            text.find("( 'y'").unwrap(),
+                            text.find("e )").unwrap() + 3
+                        ),
+                        special: Span::new(letter, letter + 10),
+                    }
+                ),
+                (
+                    "g",
+                    analysis::ExceptionIssue::Undefined {
+                        exception: Span::new(g, g + 1),
+                        name: "g",
+                        usage: Span::new(g, g + 1),
+                    }
+                ),
+            ]
+        );
+    }
+
     #[test]
     fn parse() {
         let text = include_str!("../grammar.ebnf");
@@ -33,18 +421,397 @@ mod tests {
                     println!(
                         "{}: {:?} -- {:?}",
                         k,
-                        v.iter().map(|c| c.text).collect::<Vec<_>>(),
+                        v.iter().map(|c| &c.text).collect::<Vec<_>>(),
                         res.tokens.get(k),
                     );
                 }
             }
             Err(err) => eprintln!(
-                "\x1b[31m{}\x1b[1m{}\x1b[22m{}\x1b[0m\n{}",
-                &text[(err.span.start - 20)..(err.span.start)],
-                &text[err.span.start..err.span.end],
-                &text[(err.span.end)..(err.span.end + 20)],
-                err.message,
+                "{}",
+                err.render(&SourceMap::new("grammar.ebnf", text), true)
             ),
         }
     }
+
+    #[test]
+    fn into_owned() {
+        let text = include_str!("../grammar.ebnf").to_string();
+        let syntax = Parser::new(Lexer::new(&text)).parse().unwrap().syntax;
+        let owned: ast::Syntax<'static> = syntax.clone().into_owned();
+        assert_eq!(owned, syntax);
+        drop(syntax);
+        drop(text);
+        let handle = std::thread::spawn(move || owned.rules.len());
+        assert!(handle.join().unwrap() > 0);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn serde() {
+        let text = "a = 'x' | b ;";
+        let res = Parser::new(Lexer::new(text)).parse().unwrap();
+        let json = serde_json::to_string(&res.syntax).unwrap();
+        assert!(json.starts_with(r#"{"span":{"start":0,"end":13},"rules":[{"span":{"start":0,"end":13},"name":"a","definitions":[{"span":{"start":4,"end":7},"terms":[{"span":{"start":4,"end":7},"factor":{"span":{"start":4,"end":7},"repetition":null,"primary":{"span":{"start":4,"end":7},"kind":{"terminal_string":"x"}}},"exception":null}]}"#));
+        let syntax: ast::Syntax<'static> = serde_json::from_str(&json).unwrap();
+        assert_eq!(syntax, res.syntax);
+
+        let text = include_str!("../grammar.ebnf");
+        let res = Parser::new(Lexer::new(text)).parse().unwrap();
+        let json = serde_json::to_string(&res).unwrap();
+        let parsed: ParseResult = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.syntax, res.syntax);
+        assert_eq!(parsed.comments, res.comments);
+        assert_eq!(
+            serde_json::to_string(&res.tokens[..3]).unwrap(),
+            r#"[{"kind":{"identifier":"letter"},"span":{"start":0,"end":6}},{"kind":"equal","span":{"start":10,"end":11}},{"kind":{"terminal":"A"},"span":{"start":12,"end":15}}]"#
+        );
+
+        let text = "(* a *) x = 'y' (* b *) ; (* c *)";
+        let res = Parser::new(Lexer::new(text)).parse().unwrap();
+        assert_eq!(
+            serde_json::to_string(&res.comments).unwrap(),
+            r#"{"0":[{"span":{"start":0,"end":7},"text":" a "}],"3":[{"span":{"start":16,"end":23},"text":" b "}],"4":[{"span":{"start":26,"end":33},"text":" c "}]}"#
+        );
+    }
+
+    #[test]
+    fn visit_mut() {
+        struct Rename;
+
+        impl<'src> visit::VisitorMut<'src> for Rename {
+            fn visit_syntax_rule_mut(&mut self, node: &mut ast::SyntaxRule<'src>) {
+                node.name = node.name.to_uppercase().into();
+                visit::walk_syntax_rule_mut(self, node);
+            }
+
+            fn visit_syntactic_primary_mut(&mut self, node: &mut ast::SyntacticPrimary<'src>) {
+                if let SyntacticPrimaryKind::MetaIdentifier(name) = &mut node.kind {
+                    *name = name.to_uppercase().into();
+                }
+                visit::walk_syntactic_primary_mut(self, node);
+            }
+        }
+
+        let text = "a = b , [ c - ( d | e ) ] ;";
+        let mut syntax = Parser::new(Lexer::new(text)).parse().unwrap().syntax;
+        visit::VisitorMut::visit_syntax_mut(&mut Rename, &mut syntax);
+        assert_eq!(
+            syntax,
+            Parser::new(Lexer::new("A = B , [ C - ( D | E ) ] ;"))
+                .parse()
+                .unwrap()
+                .syntax
+        );
+    }
+
+    #[test]
+    fn cst() {
+        let text = include_str!("../grammar.ebnf");
+        let res = Parser::new(Lexer::new(text)).parse().unwrap();
+        let cst = cst::CstNode::new(&res, text);
+        assert_eq!(cst.to_string(), text);
+        assert_eq!(cst.text_len(), text.len());
+
+        let text = "(* doc *)\r\na = 2 * b - ( ) , [ 'c' (* inner *) ] ;\r\n";
+        let res = Parser::new(Lexer::new(text)).parse().unwrap();
+        let cst = cst::CstNode::new(&res, text).into_owned();
+        assert_eq!(cst.to_string(), text);
+        let rule = cst.child_nodes().next().unwrap();
+        assert_eq!(rule.kind, cst::CstNodeKind::SyntaxRule);
+        assert_eq!(rule.to_string(), "a = 2 * b - ( ) , [ 'c' (* inner *) ] ;");
+        let term = rule
+            .child_nodes()
+            .next()
+            .unwrap()
+            .child_nodes()
+            .next()
+            .unwrap();
+        assert_eq!(
+            term.child_nodes()
+                .map(|node| (node.kind, node.to_string()))
+                .collect::<Vec<_>>(),
+            [
+                (cst::CstNodeKind::SyntacticFactor, "2 * b".to_string()),
+                (cst::CstNodeKind::SyntacticException, "( )".to_string()),
+            ]
+        );
+        assert_eq!(
+            cst.tokens()
+                .iter()
+                .filter(|token| !token.kind.is_trivia())
+                .count(),
+            res.tokens.len()
+        );
+
+        let (res, errors) =
+            Parser::new(Lexer::new("a = b ; c = d e ;\nf = g ;")).parse_recovering();
+        assert!(!errors.is_empty());
+        let text = "a = b ; c = d e ;\nf = g ;";
+        assert_eq!(cst::CstNode::new(&res, text).to_string(), text);
+    }
+
+    #[test]
+    fn attach_comments() {
+        let text = "(* header *)\n\n(* a rule *)\n(* with docs *)\na = b (* the b *) , c\n  (* before pipe *)\n  | (* the d *) d ; (* end of a *)\n\n(* not docs *)\n\ne = 'e' ;\n(* dangling at the end *)\n";
+        let res = Parser::new(Lexer::new(text)).parse().unwrap();
+        let comments = comments::AttachedComments::new(&res, text);
+        let texts = |comments: &[&ast::Comment]| {
+            comments
+                .iter()
+                .map(|comment| comment.text.trim().to_string())
+                .collect::<Vec<_>>()
+        };
+
+        let [a, e] = &res.syntax.rules[..] else {
+            panic!("expected two rules");
+        };
+        let a_comments = comments.rule(a).unwrap();
+        assert_eq!(
+            texts(&a_comments.leading),
+            ["header", "a rule", "with docs"]
+        );
+        assert_eq!(texts(&a_comments.trailing), ["end of a"]);
+        assert_eq!(
+            comments.doc_comment(a).as_deref(),
+            Some("a rule\nwith docs")
+        );
+        let terms = &a.definitions[0].terms;
+        let b_comments = comments.term(&terms[0]).unwrap();
+        assert_eq!(texts(&b_comments.trailing), ["the b"]);
+        let c_comments = comments.term(&terms[1]).unwrap();
+        assert_eq!(texts(&c_comments.trailing), ["before pipe"]);
+        let d_comments = comments.term(&a.definitions[1].terms[0]).unwrap();
+        assert_eq!(texts(&d_comments.leading), ["the d"]);
+
+        assert_eq!(texts(&comments.rule(e).unwrap().leading), ["not docs"]);
+        assert_eq!(comments.doc_comment(e), None);
+        assert_eq!(
+            texts(&comments.rule(e).unwrap().trailing),
+            ["dangling at the end"]
+        );
+        assert!(comments.dangling().is_empty());
+
+        let text = "(* not docs *)\n \t\n(* docs *)\na = 'a' ;\n";
+        let res = Parser::new(Lexer::new(text)).parse().unwrap();
+        let comments = comments::AttachedComments::new(&res, text);
+        assert_eq!(
+            comments.doc_comment(&res.syntax.rules[0]).as_deref(),
+            Some("docs")
+        );
+    }
+
+    #[test]
+    fn parse_recovering() {
+        let text = "a 'x' ;\nb = 'y' 'z' ;\nc = d\ne = ( f ;\ng = # 'h' ;\n";
+        let (res, errors) = Parser::new(Lexer::new(text)).parse_recovering();
+        let messages: Vec<_> = errors
+            .iter()
+            .map(|err| (&text[err.span.start..err.span.end], err.message.as_str()))
+            .collect();
+        assert_eq!(
+            messages,
+            [
+                ("'x'", "Expected '=', was '\"x\"'"),
+                ("'z'", "Expected ';', was '\"z\"'"),
+                ("e", "Expected ';', was 'e'"),
+                (";", "Expected ')', was ';'"),
+                ("#", "Illegal character '#'"),
+            ]
+        );
+        assert_eq!(
+            res.syntax
+                .rules
+                .iter()
+                .map(|rule| &*rule.name)
+                .collect::<Vec<_>>(),
+            ["a", "c", "g"]
+        );
+    }
+
+    #[test]
+    fn build_grammar() {
+        use crate::{builder::*, error::GrammarError};
+
+        let syntax = Grammar::new()
+            .rule(
+                "list",
+                seq([
+                    t("["),
+                    opt(seq([id("item"), rep(seq([t(","), id("item")]))])),
+                    t("]"),
+                ]),
+            )
+            .rule(
+                "item",
+                alt([
+                    except(id("letter"), alt([t("x"), t("y")])),
+                    times(2, seq([id("letter"), id("digit")])),
+                    special("any digit"),
+                ]),
+            )
+            .rule("quote", alt([t("'"), t("\"")]))
+            .build()
+            .unwrap();
+        let text = syntax.to_string();
+        assert_eq!(
+            text,
+            "list = '[' , [ item , { ',' , item } ] , ']' ;\n\
+             item = letter - ( 'x' | 'y' ) | 2 * ( letter , digit ) | ? any digit ? ;\n\
+             quote = \"'\" | '\"' ;\n"
+        );
+        assert_eq!(
+            Parser::new(Lexer::new(&text)).parse().unwrap().syntax,
+            syntax
+        );
+
+        let syntax = Grammar::new()
+            .rule("a", seq([opt(alt([id("b"), empty()])), t("c")]))
+            .rule("d", empty())
+            .build()
+            .unwrap();
+        let text = syntax.to_string();
+        assert_eq!(text, "a = [ b | ] , 'c' ;\nd = ;\n");
+        assert_eq!(
+            Parser::new(Lexer::new(&text)).parse().unwrap().syntax,
+            syntax
+        );
+
+        for (grammar, error) in [
+            (Grammar::new(), GrammarError::Empty),
+            (
+                Grammar::new().rule("a", t("x")).rule("a", t("y")),
+                GrammarError::DuplicateRule("a".into()),
+            ),
+            (
+                Grammar::new().rule("a b", t("x")),
+                GrammarError::InvalidIdentifier("a b".into()),
+            ),
+            (
+                Grammar::new().rule("a", id("1b")),
+                GrammarError::InvalidIdentifier("1b".into()),
+            ),
+            (
+                Grammar::new().rule("a", t(" x")),
+                GrammarError::InvalidTerminal(" x".into()),
+            ),
+            (
+                Grammar::new().rule("a", t("'\"")),
+                GrammarError::InvalidTerminal("'\"".into()),
+            ),
+            (
+                Grammar::new().rule("a", special("?")),
+                GrammarError::InvalidSpecialSequence("?".into()),
+            ),
+        ] {
+            assert_eq!(grammar.build(), Err(error));
+        }
+
+        let options = LexerOptions {
+            spaced_identifiers: true,
+            ..Default::default()
+        };
+        let syntax = Grammar::with_options(options)
+            .rule("a b", id("c d"))
+            .build()
+            .unwrap();
+        let text = syntax.to_string();
+        let parsed = Parser::new(Lexer::with_options(&text, options)).parse();
+        assert_eq!(parsed.unwrap().syntax, syntax);
+    }
+
+    #[test]
+    fn span() {
+        let text = "a = 'b' ;";
+        let span = Span::from(4..7);
+        assert_eq!(span, Span::new(4, 7));
+        assert_eq!(span.slice(text), "'b'");
+        assert_eq!(span.len(), 3);
+        assert!(!span.is_empty());
+        assert!(Span::new(5, 4).is_empty());
+        assert_eq!(Span::new(5, 4).slice(text), "");
+        assert!(span.contains(4) && !span.contains(7));
+        assert!(span.contains_span(Span::new(5, 7)) && !span.contains_span(Span::new(3, 5)));
+        assert_eq!(span.merge(Span::new(0, 1)), Span::new(0, 7));
+        assert_eq!(std::ops::Range::from(span), 4..7);
+
+        let mut spans = vec![Span::new(4, 7), Span::new(0, 9), Span::new(0, 1)];
+        spans.sort();
+        assert_eq!(spans, [Span::new(0, 1), Span::new(0, 9), Span::new(4, 7)]);
+        let set: std::collections::HashSet<_> = spans.into_iter().collect();
+        assert!(set.contains(&span));
+
+        let token = Token::new(TokenKind::Terminal("b".into()), span);
+        assert_eq!(token.kind.to_string(), "\"b\"");
+    }
+
+    #[test]
+    fn lex_trivia() {
+        let text = "a = 'b' # (* c *)\n\t| d ;";
+        let options = LexerOptions {
+            trivia: true,
+            ..Default::default()
+        };
+        let tokens: Vec<_> = Lexer::with_options(text, options)
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(
+            tokens
+                .iter()
+                .map(|token| token.span.slice(text))
+                .collect::<String>(),
+            text
+        );
+        let kinds: Vec<_> = tokens.iter().map(|token| &token.kind).collect();
+        assert_eq!(kinds[6], &TokenKind::Error("#".into()));
+        assert_eq!(kinds[8], &TokenKind::Comment(" c ".into()));
+        assert_eq!(kinds[9], &TokenKind::Whitespace("\n\t".into()));
+
+        // Without trivia, whitespace is skipped and lexing continues after errors
+        let kinds: Vec<_> = Lexer::new("a # 'b")
+            .map(|token| token.map(|token| token.kind))
+            .collect();
+        assert!(matches!(
+            &kinds[..],
+            [
+                Ok(TokenKind::Identifier(_)),
+                Ok(TokenKind::Error(_)),
+                Err(error::SyntaxError { .. })
+            ]
+        ));
+
+        let res = Parser::new(Lexer::with_options(text, options)).parse();
+        assert_eq!(res.unwrap_err().message, "Illegal character '#'");
+        let (res, errors) = Parser::new(Lexer::with_options(text, options)).parse_recovering();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(res.syntax.rules[0].definitions.len(), 2);
+    }
+
+    #[test]
+    fn lex_unicode_identifiers() {
+        let text = "größe = 数字 , { 数字_2 } - 'ß' ;";
+        let options = LexerOptions {
+            unicode_identifiers: true,
+            ..Default::default()
+        };
+        let tokens: Vec<_> = Lexer::with_options(text, options)
+            .map(|token| token.unwrap())
+            .filter(|token| matches!(token.kind, TokenKind::Identifier(_)))
+            .map(|token| (token.span.slice(text), token.span))
+            .collect();
+        assert_eq!(
+            tokens,
+            [
+                ("größe", Span::new(0, 7)),
+                ("数字", Span::new(10, 16)),
+                ("数字_2", Span::new(21, 29)),
+            ]
+        );
+        assert!(Parser::new(Lexer::with_options(text, options))
+            .parse()
+            .is_ok());
+
+        let err = Parser::new(Lexer::new(text)).parse().unwrap_err();
+        assert_eq!(err.message, "Illegal character 'ö'");
+        assert_eq!(err.span, Span::new(2, 4));
+    }
 }
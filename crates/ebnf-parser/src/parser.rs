@@ -1,4 +1,4 @@
-use std::{collections::HashMap, mem};
+use std::{collections::BTreeMap, mem};
 
 use crate::{
     ast::*,
@@ -8,9 +8,12 @@ use crate::{
     Lexer,
 };
 
-pub type CommentMap<'src> = HashMap<usize, Vec<Comment<'src>>>;
+/// Maps the index of a token to the comments directly before it, ordered by the index. The
+/// comments after the last token are stored with the index `tokens.len()`.
+pub type CommentMap<'src> = BTreeMap<usize, Vec<Comment<'src>>>;
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ParseResult<'src> {
     pub comments: CommentMap<'src>,
     pub tokens: Vec<Token<'src>>,
@@ -25,6 +28,9 @@ pub struct Parser<'src> {
     curr_span: Span,
     tokens: Vec<Token<'src>>,
     comments: CommentMap<'src>,
+    /// Whether to record errors in `errors` and resynchronize instead of bailing out
+    recover: bool,
+    errors: Vec<SyntaxError>,
 }
 
 impl<'src> Parser<'src> {
@@ -36,7 +42,9 @@ impl<'src> Parser<'src> {
             prev_span: Span::new(0, 1),
             curr_span: Span::new(0, 1),
             tokens: vec![],
-            comments: HashMap::new(),
+            comments: BTreeMap::new(),
+            recover: false,
+            errors: vec![],
         }
     }
 
@@ -55,13 +63,115 @@ impl<'src> Parser<'src> {
         })
     }
 
+    /// Parses the whole input without stopping at the first error.
+    ///
+    /// Whenever a syntax rule cannot be parsed, the error is recorded and parsing resumes at the
+    /// next `;` or at the start of the next rule. A missing `=` or `;` is reported without
+    /// discarding the rule. The returned [`Syntax`] only contains the rules that could be
+    /// recovered and may be empty.
+    pub fn parse_recovering(mut self) -> (ParseResult<'src>, Vec<SyntaxError>) {
+        self.recover = true;
+        let start = self.curr_span.start;
+        let syntax = match self.next().and_then(|_| self.syntax()) {
+            Ok(syntax) => syntax,
+            Err(err) => {
+                self.errors.push(err);
+                Syntax {
+                    span: Span::new(start, self.prev_span.end),
+                    rules: vec![],
+                }
+            }
+        };
+        // add prev_tok to tokens list
+        self.next()
+            .expect("lexer errors are recorded instead of returned in recovering mode");
+
+        let mut errors = self.errors;
+        errors.sort_by_key(|err| err.span.start);
+        (
+            ParseResult {
+                comments: self.comments,
+                tokens: self.tokens,
+                syntax,
+            },
+            errors,
+        )
+    }
+
+    /// Returns the next token which is not whitespace or an illegal character
+    fn next_token(&mut self) -> Result<Option<Token<'src>>, SyntaxError> {
+        loop {
+            let err = match self.lexer.next_token() {
+                Ok(Some(Token {
+                    kind: TokenKind::Whitespace(_),
+                    ..
+                })) => continue,
+                Ok(Some(Token {
+                    kind: TokenKind::Error(text),
+                    span,
+                })) => SyntaxError::new(span, format!("Illegal character '{text}'").into()),
+                Err(err) => err,
+                res => return res,
+            };
+            match self.recover {
+                true => self.errors.push(err),
+                false => return Err(err),
+            }
+        }
+    }
+
+    /// Whether the current token is an identifier followed by a `=`
+    fn at_rule_start(&self) -> bool {
+        if !matches!(
+            self.curr_tok,
+            Some(Token {
+                kind: TokenKind::Identifier(_),
+                ..
+            })
+        ) {
+            return false;
+        }
+        let mut lexer = self.lexer.clone();
+        loop {
+            match lexer.next_token() {
+                Ok(Some(Token {
+                    kind: TokenKind::Comment(_) | TokenKind::Whitespace(_) | TokenKind::Error(_),
+                    ..
+                }))
+                | Err(_) => continue,
+                Ok(Some(Token {
+                    kind: TokenKind::Equal,
+                    ..
+                })) => return true,
+                Ok(_) => return false,
+            }
+        }
+    }
+
+    /// Skips tokens until after the next `;` or until the start of the next rule
+    fn synchronize(&mut self, tokens_before: usize) -> Result<(), SyntaxError> {
+        loop {
+            match &self.curr_tok {
+                None => return Ok(()),
+                Some(Token {
+                    kind: TokenKind::Semicolon,
+                    ..
+                }) => return self.next(),
+                Some(_) if self.tokens.len() != tokens_before && self.at_rule_start() => {
+                    return Ok(())
+                }
+                Some(_) => self.next()?,
+            }
+        }
+    }
+
     fn next(&mut self) -> Result<(), SyntaxError> {
         if let Some(prev_tok) = self.prev_tok.take() {
             self.tokens.push(prev_tok);
         }
 
         self.prev_tok = self.curr_tok.take();
-        self.curr_tok = self.lexer.next_token()?;
+        self.curr_tok = self.next_token()?;
 
         let mut comments = vec![];
         while let Some(Token {
@@ -71,7 +181,7 @@ impl<'src> Parser<'src> {
         {
             let comment = self.curr_tok.take().expect("`curr_tok` is a comment token");
             comments.push(Comment::try_from(comment).expect("`comment` is a comment token"));
-            self.curr_tok = self.lexer.next_token()?;
+            self.curr_tok = self.next_token()?;
         }
         if !comments.is_empty() {
             self.comments.insert(
@@ -90,7 +200,7 @@ impl<'src> Parser<'src> {
     }
 
     fn is_kind(&mut self, kind: TokenKind) -> Result<bool, SyntaxError> {
-        Ok(matches!(self.curr_tok, Some(Token { kind: tok_kind, .. }) if tok_kind == kind))
+        Ok(matches!(&self.curr_tok, Some(Token { kind: tok_kind, .. }) if *tok_kind == kind))
     }
 
     fn expect(&mut self, kind: TokenKind) -> Result<(), SyntaxError> {
@@ -115,9 +225,17 @@ impl<'src> Parser<'src> {
         let mut rules = vec![];
 
         while self.curr_tok.is_some() {
-            rules.push(self.syntax_rule()?);
+            let tokens_before = self.tokens.len();
+            match self.syntax_rule() {
+                Ok(rule) => rules.push(rule),
+                Err(err) if self.recover => {
+                    self.errors.push(err);
+                    self.synchronize(tokens_before)?;
+                }
+                Err(err) => return Err(err),
+            }
         }
-        if rules.is_empty() {
+        if rules.is_empty() && (!self.recover || self.errors.is_empty()) {
             return Err(SyntaxError::new(
                 self.curr_span,
                 "Syntax requires at least on syntax rule".into(),
@@ -133,11 +251,11 @@ impl<'src> Parser<'src> {
     fn syntax_rule(&mut self) -> Result<SyntaxRule<'src>, SyntaxError> {
         let start = self.curr_span.start;
 
-        let name = match self.curr_tok {
+        let name = match &self.curr_tok {
             Some(Token {
                 kind: TokenKind::Identifier(name),
                 ..
-            }) => name,
+            }) => name.clone(),
             _ => {
                 return Err(SyntaxError::new(
                     self.curr_span,
@@ -147,9 +265,19 @@ impl<'src> Parser<'src> {
         };
         self.next()?;
 
-        self.expect(TokenKind::Equal)?;
+        match self.expect(TokenKind::Equal) {
+            // Act as if the `=` was there
+            Err(err) if self.recover => self.errors.push(err),
+            res => res?,
+        }
         let definitions = self.definitions_list()?;
-        self.expect(TokenKind::Semicolon)?;
+        match self.expect(TokenKind::Semicolon) {
+            // Only keep the rule if the next one starts right after it
+            Err(err) if self.recover && (self.curr_tok.is_none() || self.at_rule_start()) => {
+                self.errors.push(err)
+            }
+            res => res?,
+        }
 
         Ok(SyntaxRule {
             span: Span::new(start, self.prev_span.end),
@@ -234,7 +362,7 @@ impl<'src> Parser<'src> {
         let kind = match self
             .curr_tok
             .as_ref()
-            .map_or(TokenKind::Semicolon, |tok| tok.kind)
+            .map_or(TokenKind::Semicolon, |tok| tok.kind.clone())
         {
             TokenKind::LBracket => SyntacticPrimaryKind::OptionalSequence(
                 self.delimited_definitions_list(TokenKind::RBracket)?,
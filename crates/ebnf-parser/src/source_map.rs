@@ -0,0 +1,157 @@
+use std::fmt::Display;
+
+use crate::span::Span;
+
+/// A position in a source text with 1-based line and column numbers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LineCol {
+    pub line: usize,
+    pub col: usize,
+}
+
+impl Display for LineCol {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.line, self.col)
+    }
+}
+
+/// The unit in which columns are counted
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnUnit {
+    /// Count bytes of the UTF-8 encoding, which is what [`Span`]s use
+    Utf8,
+    /// Count code units of the UTF-16 encoding, as used by the Language Server Protocol
+    Utf16,
+}
+
+/// Converts between byte offsets and line/column positions in a source text
+#[derive(Debug, Clone)]
+pub struct LineIndex<'src> {
+    text: &'src str,
+    /// Byte offset of the first character of each line
+    line_starts: Vec<usize>,
+}
+
+impl<'src> LineIndex<'src> {
+    pub fn new(text: &'src str) -> Self {
+        let mut line_starts = vec![0];
+        line_starts.extend(text.match_indices('\n').map(|(index, _)| index + 1));
+        Self { text, line_starts }
+    }
+
+    pub fn text(&self) -> &'src str {
+        self.text
+    }
+
+    pub fn line_count(&self) -> usize {
+        self.line_starts.len()
+    }
+
+    /// Returns the span of the given 1-based line without its line ending
+    pub fn line_span(&self, line: usize) -> Option<Span> {
+        let start = *self.line_starts.get(line.checked_sub(1)?)?;
+        let end = self
+            .line_starts
+            .get(line)
+            .map_or(self.text.len(), |next_start| next_start - 1);
+        let end = match self.text[start..end].ends_with('\r') {
+            true => end - 1,
+            false => end,
+        };
+        Some(Span::new(start, end))
+    }
+
+    /// Converts a byte offset into a line/column position. Offsets past the end of the text are
+    /// clamped to the end and offsets inside a multibyte character point to its start.
+    pub fn line_col(&self, offset: usize, unit: ColumnUnit) -> LineCol {
+        let mut offset = offset.min(self.text.len());
+        while !self.text.is_char_boundary(offset) {
+            offset -= 1;
+        }
+        let line = self.line_starts.partition_point(|start| *start <= offset);
+        let line_text = &self.text[self.line_starts[line - 1]..offset];
+        let col = match unit {
+            ColumnUnit::Utf8 => line_text.len(),
+            ColumnUnit::Utf16 => line_text.encode_utf16().count(),
+        };
+        LineCol { line, col: col + 1 }
+    }
+
+    /// Converts both ends of a span into line/column positions
+    pub fn span_line_cols(&self, span: Span, unit: ColumnUnit) -> (LineCol, LineCol) {
+        (
+            self.line_col(span.start, unit),
+            self.line_col(span.end, unit),
+        )
+    }
+
+    /// Converts a line/column position back into a byte offset. Returns `None` if the position
+    /// lies outside the text or inside a character.
+    pub fn offset(&self, pos: LineCol, unit: ColumnUnit) -> Option<usize> {
+        let line_start = *self.line_starts.get(pos.line.checked_sub(1)?)?;
+        let line_end = self
+            .line_starts
+            .get(pos.line)
+            .map_or(self.text.len(), |next_start| next_start - 1);
+        let col = pos.col.checked_sub(1)?;
+        let offset = match unit {
+            ColumnUnit::Utf8 => line_start + col,
+            ColumnUnit::Utf16 => {
+                let mut units = 0;
+                let mut offset = line_start;
+                for c in self.text[line_start..line_end].chars() {
+                    if units >= col {
+                        break;
+                    }
+                    units += c.len_utf16();
+                    offset += c.len_utf8();
+                }
+                if units != col {
+                    return None;
+                }
+                offset
+            }
+        };
+        match offset <= line_end && self.text.is_char_boundary(offset) {
+            true => Some(offset),
+            false => None,
+        }
+    }
+}
+
+/// A named source text, used to point to locations like `grammar.ebnf:3:7`
+#[derive(Debug, Clone)]
+pub struct SourceMap<'src> {
+    name: String,
+    line_index: LineIndex<'src>,
+}
+
+impl<'src> SourceMap<'src> {
+    pub fn new(name: impl Into<String>, text: &'src str) -> Self {
+        Self {
+            name: name.into(),
+            line_index: LineIndex::new(text),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn text(&self) -> &'src str {
+        self.line_index.text()
+    }
+
+    pub fn line_index(&self) -> &LineIndex<'src> {
+        &self.line_index
+    }
+
+    /// Formats the start of a span as `name:line:col`, counting columns in UTF-8 bytes
+    pub fn location(&self, span: Span) -> String {
+        format!(
+            "{}:{}",
+            self.name,
+            self.line_index.line_col(span.start, ColumnUnit::Utf8),
+        )
+    }
+}
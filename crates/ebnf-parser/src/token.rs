@@ -1,14 +1,26 @@
-use std::fmt::{Debug, Display};
+use std::{
+    borrow::Cow,
+    fmt::{Debug, Display},
+};
 
 use crate::span::Span;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// With the `serde` feature, a token kind is serialized like a
+/// [`SyntacticPrimaryKind`](crate::ast::SyntacticPrimaryKind), for example as
+/// `{ "terminal": "x" }` or `"semicolon"`. Deserialized token kinds always own their text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum TokenKind<'src> {
-    Identifier(&'src str),
-    Terminal(&'src str),
-    Comment(&'src str),
-    SpecialSeq(&'src str),
+    Identifier(Cow<'src, str>),
+    Terminal(Cow<'src, str>),
+    Comment(Cow<'src, str>),
+    SpecialSeq(Cow<'src, str>),
     Integer(usize),
+    /// Only emitted with [`LexerOptions::trivia`](crate::LexerOptions::trivia)
+    Whitespace(Cow<'src, str>),
+    /// An illegal character
+    Error(Cow<'src, str>),
 
     LBrace,
     RBrace,
@@ -32,6 +44,7 @@ impl Display for TokenKind<'_> {
             TokenKind::Comment(text) => write!(f, "(* {text} *)"),
             TokenKind::SpecialSeq(text) => write!(f, "? {text} ?"),
             TokenKind::Integer(num) => write!(f, "{num}"),
+            TokenKind::Whitespace(text) | TokenKind::Error(text) => write!(f, "{text}"),
             TokenKind::LBrace => write!(f, "{{"),
             TokenKind::RBrace => write!(f, "}}"),
             TokenKind::LBracket => write!(f, "["),
@@ -50,13 +63,14 @@ impl Display for TokenKind<'_> {
 
 /// A token with positional information
 #[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Token<'src> {
     pub kind: TokenKind<'src>,
     pub span: Span,
 }
 
 impl<'src> Token<'src> {
-    pub(crate) fn new(kind: TokenKind<'src>, span: Span) -> Self {
+    pub fn new(kind: TokenKind<'src>, span: Span) -> Self {
         Self { kind, span }
     }
 }
@@ -0,0 +1,219 @@
+//! Traversal of the AST.
+//!
+//! Implement [`Visitor`] or [`VisitorMut`] and override the methods for the nodes of interest.
+//! Every method defaults to the matching `walk_*` function, which visits the children of the
+//! node. Overriding methods should call it as well, unless the children are to be skipped.
+//!
+//! ```
+//! use ebnf_parser::{ast::*, visit::*, Lexer, Parser};
+//!
+//! struct References<'ast>(Vec<&'ast str>);
+//!
+//! impl<'ast, 'src> Visitor<'ast, 'src> for References<'ast> {
+//!     fn visit_syntactic_primary(&mut self, node: &'ast SyntacticPrimary<'src>) {
+//!         if let SyntacticPrimaryKind::MetaIdentifier(name) = &node.kind {
+//!             self.0.push(name);
+//!         }
+//!         walk_syntactic_primary(self, node);
+//!     }
+//! }
+//!
+//! let syntax = Parser::new(Lexer::new("a = b , { c } ;")).parse().unwrap().syntax;
+//! let mut references = References(vec![]);
+//! references.visit_syntax(&syntax);
+//! assert_eq!(references.0, ["b", "c"]);
+//! ```
+
+use crate::ast::*;
+
+/// Visits the nodes of an AST by reference
+pub trait Visitor<'ast, 'src> {
+    fn visit_syntax(&mut self, node: &'ast Syntax<'src>) {
+        walk_syntax(self, node)
+    }
+
+    fn visit_syntax_rule(&mut self, node: &'ast SyntaxRule<'src>) {
+        walk_syntax_rule(self, node)
+    }
+
+    fn visit_single_definition(&mut self, node: &'ast SingleDefinition<'src>) {
+        walk_single_definition(self, node)
+    }
+
+    fn visit_syntactic_term(&mut self, node: &'ast SyntacticTerm<'src>) {
+        walk_syntactic_term(self, node)
+    }
+
+    /// Called for the exception after `-` of a term, instead of
+    /// [`visit_syntactic_factor`](Visitor::visit_syntactic_factor)
+    fn visit_syntactic_exception(&mut self, node: &'ast SyntacticException<'src>) {
+        self.visit_syntactic_factor(node)
+    }
+
+    fn visit_syntactic_factor(&mut self, node: &'ast SyntacticFactor<'src>) {
+        walk_syntactic_factor(self, node)
+    }
+
+    fn visit_syntactic_primary(&mut self, node: &'ast SyntacticPrimary<'src>) {
+        walk_syntactic_primary(self, node)
+    }
+}
+
+pub fn walk_syntax<'ast, 'src, V: Visitor<'ast, 'src> + ?Sized>(
+    visitor: &mut V,
+    node: &'ast Syntax<'src>,
+) {
+    for rule in &node.rules {
+        visitor.visit_syntax_rule(rule);
+    }
+}
+
+pub fn walk_syntax_rule<'ast, 'src, V: Visitor<'ast, 'src> + ?Sized>(
+    visitor: &mut V,
+    node: &'ast SyntaxRule<'src>,
+) {
+    for definition in &node.definitions {
+        visitor.visit_single_definition(definition);
+    }
+}
+
+pub fn walk_single_definition<'ast, 'src, V: Visitor<'ast, 'src> + ?Sized>(
+    visitor: &mut V,
+    node: &'ast SingleDefinition<'src>,
+) {
+    for term in &node.terms {
+        visitor.visit_syntactic_term(term);
+    }
+}
+
+pub fn walk_syntactic_term<'ast, 'src, V: Visitor<'ast, 'src> + ?Sized>(
+    visitor: &mut V,
+    node: &'ast SyntacticTerm<'src>,
+) {
+    visitor.visit_syntactic_factor(&node.factor);
+    if let Some(exception) = &node.exception {
+        visitor.visit_syntactic_exception(exception);
+    }
+}
+
+pub fn walk_syntactic_factor<'ast, 'src, V: Visitor<'ast, 'src> + ?Sized>(
+    visitor: &mut V,
+    node: &'ast SyntacticFactor<'src>,
+) {
+    visitor.visit_syntactic_primary(&node.primary);
+}
+
+pub fn walk_syntactic_primary<'ast, 'src, V: Visitor<'ast, 'src> + ?Sized>(
+    visitor: &mut V,
+    node: &'ast SyntacticPrimary<'src>,
+) {
+    match &node.kind {
+        SyntacticPrimaryKind::OptionalSequence(definitions)
+        | SyntacticPrimaryKind::RepeatedSequence(definitions)
+        | SyntacticPrimaryKind::GroupedSequence(definitions) => {
+            for definition in definitions {
+                visitor.visit_single_definition(definition);
+            }
+        }
+        SyntacticPrimaryKind::MetaIdentifier(_)
+        | SyntacticPrimaryKind::TerminalString(_)
+        | SyntacticPrimaryKind::SpecialSequence(_)
+        | SyntacticPrimaryKind::EmptySequence => {}
+    }
+}
+
+/// Visits the nodes of an AST by mutable reference, allowing to rewrite it in place
+pub trait VisitorMut<'src> {
+    fn visit_syntax_mut(&mut self, node: &mut Syntax<'src>) {
+        walk_syntax_mut(self, node)
+    }
+
+    fn visit_syntax_rule_mut(&mut self, node: &mut SyntaxRule<'src>) {
+        walk_syntax_rule_mut(self, node)
+    }
+
+    fn visit_single_definition_mut(&mut self, node: &mut SingleDefinition<'src>) {
+        walk_single_definition_mut(self, node)
+    }
+
+    fn visit_syntactic_term_mut(&mut self, node: &mut SyntacticTerm<'src>) {
+        walk_syntactic_term_mut(self, node)
+    }
+
+    /// Called for the exception after `-` of a term, instead of
+    /// [`visit_syntactic_factor_mut`](VisitorMut::visit_syntactic_factor_mut)
+    fn visit_syntactic_exception_mut(&mut self, node: &mut SyntacticException<'src>) {
+        self.visit_syntactic_factor_mut(node)
+    }
+
+    fn visit_syntactic_factor_mut(&mut self, node: &mut SyntacticFactor<'src>) {
+        walk_syntactic_factor_mut(self, node)
+    }
+
+    fn visit_syntactic_primary_mut(&mut self, node: &mut SyntacticPrimary<'src>) {
+        walk_syntactic_primary_mut(self, node)
+    }
+}
+
+pub fn walk_syntax_mut<'src, V: VisitorMut<'src> + ?Sized>(
+    visitor: &mut V,
+    node: &mut Syntax<'src>,
+) {
+    for rule in &mut node.rules {
+        visitor.visit_syntax_rule_mut(rule);
+    }
+}
+
+pub fn walk_syntax_rule_mut<'src, V: VisitorMut<'src> + ?Sized>(
+    visitor: &mut V,
+    node: &mut SyntaxRule<'src>,
+) {
+    for definition in &mut node.definitions {
+        visitor.visit_single_definition_mut(definition);
+    }
+}
+
+pub fn walk_single_definition_mut<'src, V: VisitorMut<'src> + ?Sized>(
+    visitor: &mut V,
+    node: &mut SingleDefinition<'src>,
+) {
+    for term in &mut node.terms {
+        visitor.visit_syntactic_term_mut(term);
+    }
+}
+
+pub fn walk_syntactic_term_mut<'src, V: VisitorMut<'src> + ?Sized>(
+    visitor: &mut V,
+    node: &mut SyntacticTerm<'src>,
+) {
+    visitor.visit_syntactic_factor_mut(&mut node.factor);
+    if let Some(exception) = &mut node.exception {
+        visitor.visit_syntactic_exception_mut(exception);
+    }
+}
+
+pub fn walk_syntactic_factor_mut<'src, V: VisitorMut<'src> + ?Sized>(
+    visitor: &mut V,
+    node: &mut SyntacticFactor<'src>,
+) {
+    visitor.visit_syntactic_primary_mut(&mut node.primary);
+}
+
+pub fn walk_syntactic_primary_mut<'src, V: VisitorMut<'src> + ?Sized>(
+    visitor: &mut V,
+    node: &mut SyntacticPrimary<'src>,
+) {
+    match &mut node.kind {
+        SyntacticPrimaryKind::OptionalSequence(definitions)
+        | SyntacticPrimaryKind::RepeatedSequence(definitions)
+        | SyntacticPrimaryKind::GroupedSequence(definitions) => {
+            for definition in definitions {
+                visitor.visit_single_definition_mut(definition);
+            }
+        }
+        SyntacticPrimaryKind::MetaIdentifier(_)
+        | SyntacticPrimaryKind::TerminalString(_)
+        | SyntacticPrimaryKind::SpecialSequence(_)
+        | SyntacticPrimaryKind::EmptySequence => {}
+    }
+}
@@ -1,6 +1,11 @@
 module.exports = grammar({
     name: 'ebnf',
 
+    externals: $ => [
+        // Comments can be nested, see `src/scanner.c`
+        $.comment,
+    ],
+
     extras: $ => [
         / |\n|\t|\r/,
         $.comment,
@@ -12,7 +17,6 @@ module.exports = grammar({
         terminal: $ => /'[^']*'|"[^"]*"/,
         identifier: $ => /[a-zA-Z][a-zA-Z0-9_]*/,
         integer: $ => /[0-9]+/,
-        comment: $ => /\(\*[^*]*\*+(?:[^)*][^*]*\*+)*\)/,
         special_sequence: $ => /\?[^?]*\?/,
 
         syntax_rule: $ => seq(field('name', $.identifier), '=', field('definition', optional($._expression)), ';'),
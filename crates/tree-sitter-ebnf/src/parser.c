@@ -11,7 +11,7 @@
 #define SYMBOL_COUNT 25
 #define ALIAS_COUNT 0
 #define TOKEN_COUNT 18
-#define EXTERNAL_TOKEN_COUNT 0
+#define EXTERNAL_TOKEN_COUNT 1
 #define FIELD_COUNT 5
 #define MAX_ALIAS_SEQUENCE_LENGTH 4
 #define PRODUCTION_ID_COUNT 5
@@ -20,20 +20,20 @@ enum {
   sym_terminal = 1,
   sym_identifier = 2,
   sym_integer = 3,
-  sym_comment = 4,
-  sym_special_sequence = 5,
-  anon_sym_EQ = 6,
-  anon_sym_SEMI = 7,
-  anon_sym_PIPE = 8,
-  anon_sym_COMMA = 9,
-  anon_sym_DASH = 10,
-  anon_sym_STAR = 11,
-  anon_sym_LBRACK = 12,
-  anon_sym_RBRACK = 13,
-  anon_sym_LBRACE = 14,
-  anon_sym_RBRACE = 15,
-  anon_sym_LPAREN = 16,
-  anon_sym_RPAREN = 17,
+  sym_special_sequence = 4,
+  anon_sym_EQ = 5,
+  anon_sym_SEMI = 6,
+  anon_sym_PIPE = 7,
+  anon_sym_COMMA = 8,
+  anon_sym_DASH = 9,
+  anon_sym_STAR = 10,
+  anon_sym_LBRACK = 11,
+  anon_sym_RBRACK = 12,
+  anon_sym_LBRACE = 13,
+  anon_sym_RBRACE = 14,
+  anon_sym_LPAREN = 15,
+  anon_sym_RPAREN = 16,
+  sym_comment = 17,
   sym_syntax = 18,
   sym_syntax_rule = 19,
   sym__expression = 20,
@@ -48,7 +48,6 @@ static const char * const ts_symbol_names[] = {
   [sym_terminal] = "terminal",
   [sym_identifier] = "identifier",
   [sym_integer] = "integer",
-  [sym_comment] = "comment",
   [sym_special_sequence] = "special_sequence",
   [anon_sym_EQ] = "=",
   [anon_sym_SEMI] = ";",
@@ -62,6 +61,7 @@ static const char * const ts_symbol_names[] = {
   [anon_sym_RBRACE] = "}",
   [anon_sym_LPAREN] = "(",
   [anon_sym_RPAREN] = ")",
+  [sym_comment] = "comment",
   [sym_syntax] = "syntax",
   [sym_syntax_rule] = "syntax_rule",
   [sym__expression] = "_expression",
@@ -76,7 +76,6 @@ static const TSSymbol ts_symbol_map[] = {
   [sym_terminal] = sym_terminal,
   [sym_identifier] = sym_identifier,
   [sym_integer] = sym_integer,
-  [sym_comment] = sym_comment,
   [sym_special_sequence] = sym_special_sequence,
   [anon_sym_EQ] = anon_sym_EQ,
   [anon_sym_SEMI] = anon_sym_SEMI,
@@ -90,6 +89,7 @@ static const TSSymbol ts_symbol_map[] = {
   [anon_sym_RBRACE] = anon_sym_RBRACE,
   [anon_sym_LPAREN] = anon_sym_LPAREN,
   [anon_sym_RPAREN] = anon_sym_RPAREN,
+  [sym_comment] = sym_comment,
   [sym_syntax] = sym_syntax,
   [sym_syntax_rule] = sym_syntax_rule,
   [sym__expression] = sym__expression,
@@ -116,10 +116,6 @@ static const TSSymbolMetadata ts_symbol_metadata[] = {
     .visible = true,
     .named = true,
   },
-  [sym_comment] = {
-    .visible = true,
-    .named = true,
-  },
   [sym_special_sequence] = {
     .visible = true,
     .named = true,
@@ -172,6 +168,10 @@ static const TSSymbolMetadata ts_symbol_metadata[] = {
     .visible = true,
     .named = false,
   },
+  [sym_comment] = {
+    .visible = true,
+    .named = true,
+  },
   [sym_syntax] = {
     .visible = true,
     .named = true,
@@ -284,109 +284,96 @@ static bool ts_lex(TSLexer *lexer, TSStateId state) {
   eof = lexer->eof(lexer);
   switch (state) {
     case 0:
-      if (eof) ADVANCE(6);
+      if (eof) ADVANCE(4);
       if (lookahead == '\t' ||
           lookahead == '\n' ||
           lookahead == '\r' ||
           lookahead == ' ') SKIP(0)
       if (lookahead == '"') ADVANCE(1);
       if (lookahead == '\'') ADVANCE(2);
-      if (lookahead == '(') ADVANCE(22);
-      if (lookahead == ')') ADVANCE(23);
-      if (lookahead == '*') ADVANCE(17);
-      if (lookahead == ',') ADVANCE(15);
-      if (lookahead == '-') ADVANCE(16);
-      if (lookahead == ';') ADVANCE(13);
-      if (lookahead == '=') ADVANCE(12);
-      if (lookahead == '?') ADVANCE(5);
-      if (lookahead == '[') ADVANCE(18);
-      if (lookahead == ']') ADVANCE(19);
-      if (lookahead == '{') ADVANCE(20);
-      if (lookahead == '|') ADVANCE(14);
-      if (lookahead == '}') ADVANCE(21);
-      if (('0' <= lookahead && lookahead <= '9')) ADVANCE(9);
+      if (lookahead == '(') ADVANCE(19);
+      if (lookahead == ')') ADVANCE(20);
+      if (lookahead == '*') ADVANCE(14);
+      if (lookahead == ',') ADVANCE(12);
+      if (lookahead == '-') ADVANCE(13);
+      if (lookahead == ';') ADVANCE(10);
+      if (lookahead == '=') ADVANCE(9);
+      if (lookahead == '?') ADVANCE(3);
+      if (lookahead == '[') ADVANCE(15);
+      if (lookahead == ']') ADVANCE(16);
+      if (lookahead == '{') ADVANCE(17);
+      if (lookahead == '|') ADVANCE(11);
+      if (lookahead == '}') ADVANCE(18);
+      if (('0' <= lookahead && lookahead <= '9')) ADVANCE(7);
       if (('A' <= lookahead && lookahead <= 'Z') ||
-          ('a' <= lookahead && lookahead <= 'z')) ADVANCE(8);
+          ('a' <= lookahead && lookahead <= 'z')) ADVANCE(6);
       END_STATE();
     case 1:
-      if (lookahead == '"') ADVANCE(7);
+      if (lookahead == '"') ADVANCE(5);
       if (lookahead != 0) ADVANCE(1);
       END_STATE();
     case 2:
-      if (lookahead == '\'') ADVANCE(7);
+      if (lookahead == '\'') ADVANCE(5);
       if (lookahead != 0) ADVANCE(2);
       END_STATE();
     case 3:
-      if (lookahead == ')') ADVANCE(10);
-      if (lookahead == '*') ADVANCE(3);
-      if (lookahead != 0) ADVANCE(4);
+      if (lookahead == '?') ADVANCE(8);
+      if (lookahead != 0) ADVANCE(3);
       END_STATE();
     case 4:
-      if (lookahead == '*') ADVANCE(3);
-      if (lookahead != 0) ADVANCE(4);
-      END_STATE();
-    case 5:
-      if (lookahead == '?') ADVANCE(11);
-      if (lookahead != 0) ADVANCE(5);
-      END_STATE();
-    case 6:
       ACCEPT_TOKEN(ts_builtin_sym_end);
       END_STATE();
-    case 7:
+    case 5:
       ACCEPT_TOKEN(sym_terminal);
       END_STATE();
-    case 8:
+    case 6:
       ACCEPT_TOKEN(sym_identifier);
       if (('0' <= lookahead && lookahead <= '9') ||
           ('A' <= lookahead && lookahead <= 'Z') ||
           lookahead == '_' ||
-          ('a' <= lookahead && lookahead <= 'z')) ADVANCE(8);
+          ('a' <= lookahead && lookahead <= 'z')) ADVANCE(6);
       END_STATE();
-    case 9:
+    case 7:
       ACCEPT_TOKEN(sym_integer);
-      if (('0' <= lookahead && lookahead <= '9')) ADVANCE(9);
-      END_STATE();
-    case 10:
-      ACCEPT_TOKEN(sym_comment);
+      if (('0' <= lookahead && lookahead <= '9')) ADVANCE(7);
       END_STATE();
-    case 11:
+    case 8:
       ACCEPT_TOKEN(sym_special_sequence);
       END_STATE();
-    case 12:
+    case 9:
       ACCEPT_TOKEN(anon_sym_EQ);
       END_STATE();
-    case 13:
+    case 10:
       ACCEPT_TOKEN(anon_sym_SEMI);
       END_STATE();
-    case 14:
+    case 11:
       ACCEPT_TOKEN(anon_sym_PIPE);
       END_STATE();
-    case 15:
+    case 12:
       ACCEPT_TOKEN(anon_sym_COMMA);
       END_STATE();
-    case 16:
+    case 13:
       ACCEPT_TOKEN(anon_sym_DASH);
       END_STATE();
-    case 17:
+    case 14:
       ACCEPT_TOKEN(anon_sym_STAR);
       END_STATE();
-    case 18:
+    case 15:
       ACCEPT_TOKEN(anon_sym_LBRACK);
       END_STATE();
-    case 19:
+    case 16:
       ACCEPT_TOKEN(anon_sym_RBRACK);
       END_STATE();
-    case 20:
+    case 17:
       ACCEPT_TOKEN(anon_sym_LBRACE);
       END_STATE();
-    case 21:
+    case 18:
       ACCEPT_TOKEN(anon_sym_RBRACE);
       END_STATE();
-    case 22:
+    case 19:
       ACCEPT_TOKEN(anon_sym_LPAREN);
-      if (lookahead == '*') ADVANCE(4);
       END_STATE();
-    case 23:
+    case 20:
       ACCEPT_TOKEN(anon_sym_RPAREN);
       END_STATE();
     default:
@@ -395,33 +382,47 @@ static bool ts_lex(TSLexer *lexer, TSStateId state) {
 }
 
 static const TSLexMode ts_lex_modes[STATE_COUNT] = {
-  [0] = {.lex_state = 0},
-  [1] = {.lex_state = 0},
-  [2] = {.lex_state = 0},
-  [3] = {.lex_state = 0},
-  [4] = {.lex_state = 0},
-  [5] = {.lex_state = 0},
-  [6] = {.lex_state = 0},
-  [7] = {.lex_state = 0},
-  [8] = {.lex_state = 0},
-  [9] = {.lex_state = 0},
-  [10] = {.lex_state = 0},
-  [11] = {.lex_state = 0},
-  [12] = {.lex_state = 0},
-  [13] = {.lex_state = 0},
-  [14] = {.lex_state = 0},
-  [15] = {.lex_state = 0},
-  [16] = {.lex_state = 0},
-  [17] = {.lex_state = 0},
-  [18] = {.lex_state = 0},
-  [19] = {.lex_state = 0},
-  [20] = {.lex_state = 0},
-  [21] = {.lex_state = 0},
-  [22] = {.lex_state = 0},
-  [23] = {.lex_state = 0},
-  [24] = {.lex_state = 0},
-  [25] = {.lex_state = 0},
-  [26] = {.lex_state = 0},
+  [0] = {.lex_state = 0, .external_lex_state = 1},
+  [1] = {.lex_state = 0, .external_lex_state = 1},
+  [2] = {.lex_state = 0, .external_lex_state = 1},
+  [3] = {.lex_state = 0, .external_lex_state = 1},
+  [4] = {.lex_state = 0, .external_lex_state = 1},
+  [5] = {.lex_state = 0, .external_lex_state = 1},
+  [6] = {.lex_state = 0, .external_lex_state = 1},
+  [7] = {.lex_state = 0, .external_lex_state = 1},
+  [8] = {.lex_state = 0, .external_lex_state = 1},
+  [9] = {.lex_state = 0, .external_lex_state = 1},
+  [10] = {.lex_state = 0, .external_lex_state = 1},
+  [11] = {.lex_state = 0, .external_lex_state = 1},
+  [12] = {.lex_state = 0, .external_lex_state = 1},
+  [13] = {.lex_state = 0, .external_lex_state = 1},
+  [14] = {.lex_state = 0, .external_lex_state = 1},
+  [15] = {.lex_state = 0, .external_lex_state = 1},
+  [16] = {.lex_state = 0, .external_lex_state = 1},
+  [17] = {.lex_state = 0, .external_lex_state = 1},
+  [18] = {.lex_state = 0, .external_lex_state = 1},
+  [19] = {.lex_state = 0, .external_lex_state = 1},
+  [20] = {.lex_state = 0, .external_lex_state = 1},
+  [21] = {.lex_state = 0, .external_lex_state = 1},
+  [22] = {.lex_state = 0, .external_lex_state = 1},
+  [23] = {.lex_state = 0, .external_lex_state = 1},
+  [24] = {.lex_state = 0, .external_lex_state = 1},
+  [25] = {.lex_state = 0, .external_lex_state = 1},
+  [26] = {.lex_state = 0, .external_lex_state = 1},
+};
+
+enum {
+  ts_external_token_comment = 0,
+};
+
+static const TSSymbol ts_external_scanner_symbol_map[EXTERNAL_TOKEN_COUNT] = {
+  [ts_external_token_comment] = sym_comment,
+};
+
+static const bool ts_external_scanner_states[2][EXTERNAL_TOKEN_COUNT] = {
+  [1] = {
+    [ts_external_token_comment] = true,
+  },
 };
 
 static const uint16_t ts_parse_table[LARGE_STATE_COUNT][SYMBOL_COUNT] = {
@@ -430,7 +431,6 @@ static const uint16_t ts_parse_table[LARGE_STATE_COUNT][SYMBOL_COUNT] = {
     [sym_terminal] = ACTIONS(1),
     [sym_identifier] = ACTIONS(1),
     [sym_integer] = ACTIONS(1),
-    [sym_comment] = ACTIONS(3),
     [sym_special_sequence] = ACTIONS(1),
     [anon_sym_EQ] = ACTIONS(1),
     [anon_sym_SEMI] = ACTIONS(1),
@@ -444,23 +444,23 @@ static const uint16_t ts_parse_table[LARGE_STATE_COUNT][SYMBOL_COUNT] = {
     [anon_sym_RBRACE] = ACTIONS(1),
     [anon_sym_LPAREN] = ACTIONS(1),
     [anon_sym_RPAREN] = ACTIONS(1),
+    [sym_comment] = ACTIONS(3),
   },
   [1] = {
-    [sym_syntax] = STATE(26),
-    [sym_syntax_rule] = STATE(21),
-    [aux_sym_syntax_repeat1] = STATE(21),
+    [sym_syntax] = STATE(24),
+    [sym_syntax_rule] = STATE(18),
+    [aux_sym_syntax_repeat1] = STATE(18),
     [sym_identifier] = ACTIONS(5),
     [sym_comment] = ACTIONS(3),
   },
   [2] = {
-    [sym__expression] = STATE(10),
-    [sym__atom] = STATE(10),
-    [sym_binary_expression] = STATE(10),
-    [sym_group] = STATE(10),
+    [sym__expression] = STATE(15),
+    [sym__atom] = STATE(15),
+    [sym_binary_expression] = STATE(15),
+    [sym_group] = STATE(15),
     [sym_terminal] = ACTIONS(7),
     [sym_identifier] = ACTIONS(7),
     [sym_integer] = ACTIONS(9),
-    [sym_comment] = ACTIONS(3),
     [sym_special_sequence] = ACTIONS(7),
     [anon_sym_SEMI] = ACTIONS(11),
     [anon_sym_PIPE] = ACTIONS(11),
@@ -472,66 +472,67 @@ static const uint16_t ts_parse_table[LARGE_STATE_COUNT][SYMBOL_COUNT] = {
     [anon_sym_RBRACE] = ACTIONS(11),
     [anon_sym_LPAREN] = ACTIONS(17),
     [anon_sym_RPAREN] = ACTIONS(11),
+    [sym_comment] = ACTIONS(3),
   },
   [3] = {
-    [sym__expression] = STATE(16),
-    [sym__atom] = STATE(16),
-    [sym_binary_expression] = STATE(16),
-    [sym_group] = STATE(16),
+    [sym__expression] = STATE(20),
+    [sym__atom] = STATE(20),
+    [sym_binary_expression] = STATE(20),
+    [sym_group] = STATE(20),
     [sym_terminal] = ACTIONS(19),
     [sym_identifier] = ACTIONS(19),
     [sym_integer] = ACTIONS(9),
-    [sym_comment] = ACTIONS(3),
     [sym_special_sequence] = ACTIONS(19),
-    [anon_sym_SEMI] = ACTIONS(21),
     [anon_sym_LBRACK] = ACTIONS(13),
+    [anon_sym_RBRACK] = ACTIONS(21),
     [anon_sym_LBRACE] = ACTIONS(15),
     [anon_sym_LPAREN] = ACTIONS(17),
+    [sym_comment] = ACTIONS(3),
   },
   [4] = {
-    [sym__expression] = STATE(18),
-    [sym__atom] = STATE(18),
-    [sym_binary_expression] = STATE(18),
-    [sym_group] = STATE(18),
+    [sym__expression] = STATE(19),
+    [sym__atom] = STATE(19),
+    [sym_binary_expression] = STATE(19),
+    [sym_group] = STATE(19),
     [sym_terminal] = ACTIONS(23),
     [sym_identifier] = ACTIONS(23),
     [sym_integer] = ACTIONS(9),
-    [sym_comment] = ACTIONS(3),
     [sym_special_sequence] = ACTIONS(23),
     [anon_sym_LBRACK] = ACTIONS(13),
-    [anon_sym_RBRACK] = ACTIONS(25),
     [anon_sym_LBRACE] = ACTIONS(15),
     [anon_sym_LPAREN] = ACTIONS(17),
+    [anon_sym_RPAREN] = ACTIONS(21),
+    [sym_comment] = ACTIONS(3),
   },
   [5] = {
-    [sym__expression] = STATE(19),
-    [sym__atom] = STATE(19),
-    [sym_binary_expression] = STATE(19),
-    [sym_group] = STATE(19),
-    [sym_terminal] = ACTIONS(27),
-    [sym_identifier] = ACTIONS(27),
+    [sym__expression] = STATE(17),
+    [sym__atom] = STATE(17),
+    [sym_binary_expression] = STATE(17),
+    [sym_group] = STATE(17),
+    [sym_terminal] = ACTIONS(25),
+    [sym_identifier] = ACTIONS(25),
     [sym_integer] = ACTIONS(9),
-    [sym_comment] = ACTIONS(3),
-    [sym_special_sequence] = ACTIONS(27),
+    [sym_special_sequence] = ACTIONS(25),
+    [anon_sym_SEMI] = ACTIONS(27),
     [anon_sym_LBRACK] = ACTIONS(13),
     [anon_sym_LBRACE] = ACTIONS(15),
-    [anon_sym_RBRACE] = ACTIONS(25),
     [anon_sym_LPAREN] = ACTIONS(17),
+    [sym_comment] = ACTIONS(3),
   },
   [6] = {
-    [sym__expression] = STATE(17),
-    [sym__atom] = STATE(17),
-    [sym_binary_expression] = STATE(17),
-    [sym_group] = STATE(17),
+    [sym__expression] = STATE(21),
+    [sym__atom] = STATE(21),
+    [sym_binary_expression] = STATE(21),
+    [sym_group] = STATE(21),
     [sym_terminal] = ACTIONS(29),
     [sym_identifier] = ACTIONS(29),
     [sym_integer] = ACTIONS(9),
-    [sym_comment] = ACTIONS(3),
     [sym_special_sequence] = ACTIONS(29),
     [anon_sym_LBRACK] = ACTIONS(13),
     [anon_sym_LBRACE] = ACTIONS(15),
+    [anon_sym_RBRACE] = ACTIONS(21),
     [anon_sym_LPAREN] = ACTIONS(17),
-    [anon_sym_RPAREN] = ACTIONS(25),
+    [sym_comment] = ACTIONS(3),
   },
 };
 
@@ -551,7 +552,7 @@ static const uint16_t ts_small_parse_table[] = {
       sym_terminal,
       sym_identifier,
       sym_special_sequence,
-    STATE(11), 4,
+    STATE(10), 4,
       sym__expression,
       sym__atom,
       sym_binary_expression,
@@ -591,7 +592,7 @@ static const uint16_t ts_small_parse_table[] = {
       sym_terminal,
       sym_identifier,
       sym_special_sequence,
-    STATE(12), 4,
+    STATE(11), 4,
       sym__expression,
       sym__atom,
       sym_binary_expression,
@@ -607,35 +608,34 @@ static const uint16_t ts_small_parse_table[] = {
       anon_sym_RBRACK,
       anon_sym_RBRACE,
       anon_sym_RPAREN,
-  [94] = 3,
+  [94] = 4,
     ACTIONS(3), 1,
       sym_comment,
     ACTIONS(39), 1,
+      anon_sym_COMMA,
+    ACTIONS(41), 1,
       anon_sym_DASH,
-    ACTIONS(37), 6,
+    ACTIONS(37), 5,
       anon_sym_SEMI,
       anon_sym_PIPE,
-      anon_sym_COMMA,
       anon_sym_RBRACK,
       anon_sym_RBRACE,
       anon_sym_RPAREN,
-  [109] = 4,
+  [111] = 2,
     ACTIONS(3), 1,
       sym_comment,
-    ACTIONS(39), 1,
-      anon_sym_DASH,
-    ACTIONS(41), 1,
-      anon_sym_COMMA,
-    ACTIONS(37), 5,
+    ACTIONS(43), 7,
       anon_sym_SEMI,
       anon_sym_PIPE,
+      anon_sym_COMMA,
+      anon_sym_DASH,
       anon_sym_RBRACK,
       anon_sym_RBRACE,
       anon_sym_RPAREN,
-  [126] = 2,
+  [124] = 2,
     ACTIONS(3), 1,
       sym_comment,
-    ACTIONS(43), 7,
+    ACTIONS(45), 7,
       anon_sym_SEMI,
       anon_sym_PIPE,
       anon_sym_COMMA,
@@ -643,21 +643,22 @@ static const uint16_t ts_small_parse_table[] = {
       anon_sym_RBRACK,
       anon_sym_RBRACE,
       anon_sym_RPAREN,
-  [139] = 2,
+  [137] = 3,
     ACTIONS(3), 1,
       sym_comment,
-    ACTIONS(37), 7,
+    ACTIONS(41), 1,
+      anon_sym_DASH,
+    ACTIONS(37), 6,
       anon_sym_SEMI,
       anon_sym_PIPE,
       anon_sym_COMMA,
-      anon_sym_DASH,
       anon_sym_RBRACK,
       anon_sym_RBRACE,
       anon_sym_RPAREN,
   [152] = 2,
     ACTIONS(3), 1,
       sym_comment,
-    ACTIONS(45), 7,
+    ACTIONS(37), 7,
       anon_sym_SEMI,
       anon_sym_PIPE,
       anon_sym_COMMA,
@@ -665,70 +666,70 @@ static const uint16_t ts_small_parse_table[] = {
       anon_sym_RBRACK,
       anon_sym_RBRACE,
       anon_sym_RPAREN,
-  [165] = 5,
+  [165] = 4,
+    ACTIONS(3), 1,
+      sym_comment,
+    ACTIONS(47), 1,
+      ts_builtin_sym_end,
+    ACTIONS(49), 1,
+      sym_identifier,
+    STATE(16), 2,
+      sym_syntax_rule,
+      aux_sym_syntax_repeat1,
+  [179] = 5,
     ACTIONS(3), 1,
       sym_comment,
     ACTIONS(39), 1,
-      anon_sym_DASH,
-    ACTIONS(41), 1,
       anon_sym_COMMA,
-    ACTIONS(47), 1,
+    ACTIONS(41), 1,
+      anon_sym_DASH,
+    ACTIONS(52), 1,
       anon_sym_SEMI,
-    ACTIONS(49), 1,
+    ACTIONS(54), 1,
       anon_sym_PIPE,
-  [181] = 5,
+  [195] = 4,
+    ACTIONS(3), 1,
+      sym_comment,
+    ACTIONS(5), 1,
+      sym_identifier,
+    ACTIONS(56), 1,
+      ts_builtin_sym_end,
+    STATE(16), 2,
+      sym_syntax_rule,
+      aux_sym_syntax_repeat1,
+  [209] = 5,
     ACTIONS(3), 1,
       sym_comment,
     ACTIONS(39), 1,
-      anon_sym_DASH,
-    ACTIONS(41), 1,
       anon_sym_COMMA,
-    ACTIONS(49), 1,
+    ACTIONS(41), 1,
+      anon_sym_DASH,
+    ACTIONS(54), 1,
       anon_sym_PIPE,
-    ACTIONS(51), 1,
+    ACTIONS(58), 1,
       anon_sym_RPAREN,
-  [197] = 5,
+  [225] = 5,
     ACTIONS(3), 1,
       sym_comment,
     ACTIONS(39), 1,
-      anon_sym_DASH,
-    ACTIONS(41), 1,
       anon_sym_COMMA,
-    ACTIONS(49), 1,
+    ACTIONS(41), 1,
+      anon_sym_DASH,
+    ACTIONS(54), 1,
       anon_sym_PIPE,
-    ACTIONS(51), 1,
+    ACTIONS(58), 1,
       anon_sym_RBRACK,
-  [213] = 5,
+  [241] = 5,
     ACTIONS(3), 1,
       sym_comment,
     ACTIONS(39), 1,
-      anon_sym_DASH,
-    ACTIONS(41), 1,
       anon_sym_COMMA,
-    ACTIONS(49), 1,
+    ACTIONS(41), 1,
+      anon_sym_DASH,
+    ACTIONS(54), 1,
       anon_sym_PIPE,
-    ACTIONS(51), 1,
-      anon_sym_RBRACE,
-  [229] = 4,
-    ACTIONS(3), 1,
-      sym_comment,
-    ACTIONS(53), 1,
-      ts_builtin_sym_end,
-    ACTIONS(55), 1,
-      sym_identifier,
-    STATE(20), 2,
-      sym_syntax_rule,
-      aux_sym_syntax_repeat1,
-  [243] = 4,
-    ACTIONS(3), 1,
-      sym_comment,
-    ACTIONS(5), 1,
-      sym_identifier,
     ACTIONS(58), 1,
-      ts_builtin_sym_end,
-    STATE(20), 2,
-      sym_syntax_rule,
-      aux_sym_syntax_repeat1,
+      anon_sym_RBRACE,
   [257] = 2,
     ACTIONS(3), 1,
       sym_comment,
@@ -745,7 +746,7 @@ static const uint16_t ts_small_parse_table[] = {
     ACTIONS(3), 1,
       sym_comment,
     ACTIONS(64), 1,
-      anon_sym_EQ,
+      ts_builtin_sym_end,
   [280] = 2,
     ACTIONS(3), 1,
       sym_comment,
@@ -755,7 +756,7 @@ static const uint16_t ts_small_parse_table[] = {
     ACTIONS(3), 1,
       sym_comment,
     ACTIONS(68), 1,
-      ts_builtin_sym_end,
+      anon_sym_EQ,
 };
 
 static const uint32_t ts_small_parse_table_map[] = {
@@ -764,16 +765,16 @@ static const uint32_t ts_small_parse_table_map[] = {
   [SMALL_STATE(9)] = 54,
   [SMALL_STATE(10)] = 81,
   [SMALL_STATE(11)] = 94,
-  [SMALL_STATE(12)] = 109,
-  [SMALL_STATE(13)] = 126,
-  [SMALL_STATE(14)] = 139,
+  [SMALL_STATE(12)] = 111,
+  [SMALL_STATE(13)] = 124,
+  [SMALL_STATE(14)] = 137,
   [SMALL_STATE(15)] = 152,
   [SMALL_STATE(16)] = 165,
-  [SMALL_STATE(17)] = 181,
-  [SMALL_STATE(18)] = 197,
-  [SMALL_STATE(19)] = 213,
-  [SMALL_STATE(20)] = 229,
-  [SMALL_STATE(21)] = 243,
+  [SMALL_STATE(17)] = 179,
+  [SMALL_STATE(18)] = 195,
+  [SMALL_STATE(19)] = 209,
+  [SMALL_STATE(20)] = 225,
+  [SMALL_STATE(21)] = 241,
   [SMALL_STATE(22)] = 257,
   [SMALL_STATE(23)] = 265,
   [SMALL_STATE(24)] = 273,
@@ -785,43 +786,49 @@ static const TSParseActionEntry ts_parse_actions[] = {
   [0] = {.entry = {.count = 0, .reusable = false}},
   [1] = {.entry = {.count = 1, .reusable = false}}, RECOVER(),
   [3] = {.entry = {.count = 1, .reusable = true}}, SHIFT_EXTRA(),
-  [5] = {.entry = {.count = 1, .reusable = true}}, SHIFT(24),
-  [7] = {.entry = {.count = 1, .reusable = true}}, SHIFT(10),
+  [5] = {.entry = {.count = 1, .reusable = true}}, SHIFT(26),
+  [7] = {.entry = {.count = 1, .reusable = true}}, SHIFT(15),
   [9] = {.entry = {.count = 1, .reusable = true}}, SHIFT(25),
   [11] = {.entry = {.count = 1, .reusable = true}}, REDUCE(sym_binary_expression, 2, .production_id = 3),
-  [13] = {.entry = {.count = 1, .reusable = true}}, SHIFT(4),
-  [15] = {.entry = {.count = 1, .reusable = true}}, SHIFT(5),
-  [17] = {.entry = {.count = 1, .reusable = false}}, SHIFT(6),
-  [19] = {.entry = {.count = 1, .reusable = true}}, SHIFT(16),
-  [21] = {.entry = {.count = 1, .reusable = true}}, SHIFT(23),
-  [23] = {.entry = {.count = 1, .reusable = true}}, SHIFT(18),
-  [25] = {.entry = {.count = 1, .reusable = true}}, SHIFT(15),
-  [27] = {.entry = {.count = 1, .reusable = true}}, SHIFT(19),
-  [29] = {.entry = {.count = 1, .reusable = true}}, SHIFT(17),
-  [31] = {.entry = {.count = 1, .reusable = true}}, SHIFT(11),
+  [13] = {.entry = {.count = 1, .reusable = true}}, SHIFT(3),
+  [15] = {.entry = {.count = 1, .reusable = true}}, SHIFT(6),
+  [17] = {.entry = {.count = 1, .reusable = true}}, SHIFT(4),
+  [19] = {.entry = {.count = 1, .reusable = true}}, SHIFT(20),
+  [21] = {.entry = {.count = 1, .reusable = true}}, SHIFT(13),
+  [23] = {.entry = {.count = 1, .reusable = true}}, SHIFT(19),
+  [25] = {.entry = {.count = 1, .reusable = true}}, SHIFT(17),
+  [27] = {.entry = {.count = 1, .reusable = true}}, SHIFT(22),
+  [29] = {.entry = {.count = 1, .reusable = true}}, SHIFT(21),
+  [31] = {.entry = {.count = 1, .reusable = true}}, SHIFT(10),
   [33] = {.entry = {.count = 1, .reusable = true}}, SHIFT(14),
-  [35] = {.entry = {.count = 1, .reusable = true}}, SHIFT(12),
+  [35] = {.entry = {.count = 1, .reusable = true}}, SHIFT(11),
   [37] = {.entry = {.count = 1, .reusable = true}}, REDUCE(sym_binary_expression, 3, .production_id = 4),
-  [39] = {.entry = {.count = 1, .reusable = true}}, SHIFT(2),
-  [41] = {.entry = {.count = 1, .reusable = true}}, SHIFT(7),
+  [39] = {.entry = {.count = 1, .reusable = true}}, SHIFT(8),
+  [41] = {.entry = {.count = 1, .reusable = true}}, SHIFT(2),
   [43] = {.entry = {.count = 1, .reusable = true}}, REDUCE(sym_group, 3),
   [45] = {.entry = {.count = 1, .reusable = true}}, REDUCE(sym_group, 2),
-  [47] = {.entry = {.count = 1, .reusable = true}}, SHIFT(22),
-  [49] = {.entry = {.count = 1, .reusable = true}}, SHIFT(9),
-  [51] = {.entry = {.count = 1, .reusable = true}}, SHIFT(13),
-  [53] = {.entry = {.count = 1, .reusable = true}}, REDUCE(aux_sym_syntax_repeat1, 2),
-  [55] = {.entry = {.count = 2, .reusable = true}}, REDUCE(aux_sym_syntax_repeat1, 2), SHIFT_REPEAT(24),
-  [58] = {.entry = {.count = 1, .reusable = true}}, REDUCE(sym_syntax, 1),
-  [60] = {.entry = {.count = 1, .reusable = true}}, REDUCE(sym_syntax_rule, 4, .production_id = 2),
-  [62] = {.entry = {.count = 1, .reusable = true}}, REDUCE(sym_syntax_rule, 3, .production_id = 1),
-  [64] = {.entry = {.count = 1, .reusable = true}}, SHIFT(3),
-  [66] = {.entry = {.count = 1, .reusable = true}}, SHIFT(8),
-  [68] = {.entry = {.count = 1, .reusable = true}},  ACCEPT_INPUT(),
+  [47] = {.entry = {.count = 1, .reusable = true}}, REDUCE(aux_sym_syntax_repeat1, 2),
+  [49] = {.entry = {.count = 2, .reusable = true}}, REDUCE(aux_sym_syntax_repeat1, 2), SHIFT_REPEAT(26),
+  [52] = {.entry = {.count = 1, .reusable = true}}, SHIFT(23),
+  [54] = {.entry = {.count = 1, .reusable = true}}, SHIFT(9),
+  [56] = {.entry = {.count = 1, .reusable = true}}, REDUCE(sym_syntax, 1),
+  [58] = {.entry = {.count = 1, .reusable = true}}, SHIFT(12),
+  [60] = {.entry = {.count = 1, .reusable = true}}, REDUCE(sym_syntax_rule, 3, .production_id = 1),
+  [62] = {.entry = {.count = 1, .reusable = true}}, REDUCE(sym_syntax_rule, 4, .production_id = 2),
+  [64] = {.entry = {.count = 1, .reusable = true}},  ACCEPT_INPUT(),
+  [66] = {.entry = {.count = 1, .reusable = true}}, SHIFT(7),
+  [68] = {.entry = {.count = 1, .reusable = true}}, SHIFT(5),
 };
 
 #ifdef __cplusplus
 extern "C" {
 #endif
+void *tree_sitter_ebnf_external_scanner_create(void);
+void tree_sitter_ebnf_external_scanner_destroy(void *);
+bool tree_sitter_ebnf_external_scanner_scan(void *, TSLexer *, const bool *);
+unsigned tree_sitter_ebnf_external_scanner_serialize(void *, char *);
+void tree_sitter_ebnf_external_scanner_deserialize(void *, const char *, unsigned);
+
 #ifdef _WIN32
 #define extern __declspec(dllexport)
 #endif
@@ -852,6 +859,15 @@ extern const TSLanguage *tree_sitter_ebnf(void) {
     .alias_sequences = &ts_alias_sequences[0][0],
     .lex_modes = ts_lex_modes,
     .lex_fn = ts_lex,
+    .external_scanner = {
+      &ts_external_scanner_states[0][0],
+      ts_external_scanner_symbol_map,
+      tree_sitter_ebnf_external_scanner_create,
+      tree_sitter_ebnf_external_scanner_destroy,
+      tree_sitter_ebnf_external_scanner_scan,
+      tree_sitter_ebnf_external_scanner_serialize,
+      tree_sitter_ebnf_external_scanner_deserialize,
+    },
     .primary_state_ids = ts_primary_state_ids,
   };
   return &language;
@@ -0,0 +1,53 @@
+#include <tree_sitter/parser.h>
+#include <wctype.h>
+
+enum TokenType {
+    COMMENT,
+};
+
+void *tree_sitter_ebnf_external_scanner_create() { return NULL; }
+
+void tree_sitter_ebnf_external_scanner_destroy(void *payload) {}
+
+unsigned tree_sitter_ebnf_external_scanner_serialize(void *payload, char *buffer) { return 0; }
+
+void tree_sitter_ebnf_external_scanner_deserialize(void *payload, const char *buffer, unsigned length) {}
+
+static void advance(TSLexer *lexer) { lexer->advance(lexer, false); }
+
+// Comments may be nested as defined in ISO 14977, which cannot be expressed with a regex
+bool tree_sitter_ebnf_external_scanner_scan(void *payload, TSLexer *lexer, const bool *valid_symbols) {
+    if (!valid_symbols[COMMENT]) return false;
+
+    while (iswspace(lexer->lookahead)) lexer->advance(lexer, true);
+
+    if (lexer->lookahead != '(') return false;
+    advance(lexer);
+    if (lexer->lookahead != '*') return false;
+    advance(lexer);
+
+    unsigned depth = 1;
+    while (!lexer->eof(lexer)) {
+        if (lexer->lookahead == '(') {
+            advance(lexer);
+            if (lexer->lookahead == '*') {
+                advance(lexer);
+                depth++;
+            }
+        } else if (lexer->lookahead == '*') {
+            advance(lexer);
+            if (lexer->lookahead == ')') {
+                advance(lexer);
+                if (--depth == 0) {
+                    lexer->result_symbol = COMMENT;
+                    return true;
+                }
+            }
+        } else {
+            advance(lexer);
+        }
+    }
+
+    // Unterminated comment
+    return false;
+}